@@ -0,0 +1,187 @@
+use crate::colour::RgbColour;
+use crate::comparison::Metric;
+use crate::error_code::ErrorCode;
+use crate::float::FloatHslColour;
+use crate::float::FloatRgbColour;
+use crate::simd;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum BatchSpace {
+  Lab,
+  // Hue in degrees, saturation and lightness as percentages.
+  Hsl,
+  Oklab,
+  // Linear-light sRGB from 0 to 1.
+  Linear,
+  Xyz,
+}
+
+fn pixel_count(pixels: &[u8]) -> Result<usize, ErrorCode> {
+  if !pixels.len().is_multiple_of(4) {
+    return Err(ErrorCode::InvalidBufferLength(pixels.len()));
+  }
+  Ok(pixels.len() / 4)
+}
+
+fn check_length(expected: usize, found: usize) -> Result<(), ErrorCode> {
+  if expected != found {
+    return Err(ErrorCode::BufferSizeMismatch { expected, found });
+  }
+  Ok(())
+}
+
+// Writes three channels per RGBA pixel into `output`; alpha is ignored.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn rgba_to_space(
+  pixels: &[u8],
+  output: &mut [f32],
+  space: BatchSpace,
+) -> Result<(), ErrorCode> {
+  check_length(pixel_count(pixels)? * 3, output.len())?;
+
+  match space {
+    BatchSpace::Lab => simd::from_rgba(pixels, output, simd::linear_to_lab),
+    BatchSpace::Oklab => simd::from_rgba(pixels, output, simd::linear_to_oklab),
+    BatchSpace::Linear => simd::from_rgba(pixels, output, simd::linear),
+    BatchSpace::Xyz => simd::from_rgba(pixels, output, simd::linear_to_xyz),
+    BatchSpace::Hsl => {
+      for (pixel, values) in pixels.chunks_exact(4).zip(output.chunks_exact_mut(3)) {
+        let colour = RgbColour::from_tuple(pixel[0], pixel[1], pixel[2]);
+        let hsl = FloatRgbColour::from_rgb(&colour).to_hsl();
+        values.copy_from_slice(&[hsl.hue as f32, hsl.saturation as f32, hsl.lightness as f32]);
+      }
+    }
+  }
+  Ok(())
+}
+
+// Overwrites the RGB channels of `pixels`, clipping to sRGB and leaving alpha as it was.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn space_to_rgba(input: &[f32], pixels: &mut [u8], space: BatchSpace) -> Result<(), ErrorCode> {
+  check_length(pixel_count(pixels)? * 3, input.len())?;
+
+  match space {
+    BatchSpace::Lab => simd::to_rgba(input, pixels, simd::lab_to_linear),
+    BatchSpace::Oklab => simd::to_rgba(input, pixels, simd::oklab_to_linear),
+    BatchSpace::Linear => simd::to_rgba(input, pixels, simd::linear),
+    BatchSpace::Xyz => simd::to_rgba(input, pixels, simd::xyz_to_linear),
+    BatchSpace::Hsl => {
+      for (values, pixel) in input.chunks_exact(3).zip(pixels.chunks_exact_mut(4)) {
+        let colour = FloatHslColour::new(values[0] as f64, values[1] as f64, values[2] as f64)
+          .to_rgb()
+          .to_rgb();
+        pixel[..3].copy_from_slice(&[colour.red, colour.green, colour.blue]);
+      }
+    }
+  }
+  Ok(())
+}
+
+// Distance from every pixel to `reference`, one value per pixel in `output`.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn compare_to_reference(
+  pixels: &[u8],
+  reference: &RgbColour,
+  metric: Metric,
+  output: &mut [f32],
+) -> Result<(), ErrorCode> {
+  check_length(pixel_count(pixels)?, output.len())?;
+
+  let reference = metric.coordinates(*reference);
+  for (pixel, distance) in pixels.chunks_exact(4).zip(output.iter_mut()) {
+    let colour = RgbColour::from_tuple(pixel[0], pixel[1], pixel[2]);
+    *distance = metric.distance(&metric.coordinates(colour), &reference);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PIXELS: [u8; 12] = [255, 0, 0, 255, 102, 51, 153, 128, 0, 0, 0, 0];
+
+  mod rgba_to_space {
+    use super::*;
+
+    #[test]
+    fn matches_single_conversions() {
+      let mut output = [0.0; 9];
+      rgba_to_space(&PIXELS, &mut output, BatchSpace::Lab).unwrap();
+      let lab: (f32, f32, f32) = RgbColour::from_tuple(102, 51, 153).to_lab().into();
+      assert!((output[3] - lab.0).abs() < 1e-4);
+      assert!((output[4] - lab.1).abs() < 1e-4);
+      assert!((output[5] - lab.2).abs() < 1e-4);
+
+      rgba_to_space(&PIXELS, &mut output, BatchSpace::Hsl).unwrap();
+      assert!((output[3] - 270.0).abs() < 0.001);
+      assert!((output[4] - 50.0).abs() < 0.001);
+      assert!((output[5] - 40.0).abs() < 0.001);
+
+      rgba_to_space(&PIXELS, &mut output, BatchSpace::Oklab).unwrap();
+      assert!((output[0] - 0.628).abs() < 0.001);
+      assert!(output[6..].iter().all(|value| value.abs() < 1e-6));
+
+      rgba_to_space(&PIXELS, &mut output, BatchSpace::Xyz).unwrap();
+      let xyz = RgbColour::from_tuple(102, 51, 153).to_xyz();
+      assert_eq!(&output[3..6], &[xyz.x, xyz.y, xyz.z]);
+    }
+
+    #[test]
+    fn invalid_lengths() {
+      assert_eq!(
+        rgba_to_space(&PIXELS[..5], &mut [0.0; 3], BatchSpace::Lab),
+        Err(ErrorCode::InvalidBufferLength(5))
+      );
+      assert_eq!(
+        rgba_to_space(&PIXELS, &mut [0.0; 8], BatchSpace::Lab),
+        Err(ErrorCode::BufferSizeMismatch {
+          expected: 9,
+          found: 8
+        })
+      );
+    }
+  }
+
+  mod space_to_rgba {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+      for space in [
+        BatchSpace::Lab,
+        BatchSpace::Hsl,
+        BatchSpace::Oklab,
+        BatchSpace::Linear,
+        BatchSpace::Xyz,
+      ] {
+        let mut values = [0.0; 9];
+        rgba_to_space(&PIXELS, &mut values, space).unwrap();
+        let mut pixels = [9; 12];
+        space_to_rgba(&values, &mut pixels, space).unwrap();
+        assert_eq!(pixels, [255, 0, 0, 9, 102, 51, 153, 9, 0, 0, 0, 9]);
+      }
+    }
+  }
+
+  mod compare_to_reference {
+    use super::*;
+
+    #[test]
+    fn distances() {
+      let mut output = [0.0; 3];
+      compare_to_reference(
+        &PIXELS,
+        &RgbColour::from_tuple(255, 0, 0),
+        Metric::Rgb,
+        &mut output,
+      )
+      .unwrap();
+      assert_eq!(output[0], 0.0);
+      assert!((output[2] - 255.0).abs() < 0.001);
+    }
+  }
+}
@@ -0,0 +1,399 @@
+use colour_fun::colour::{HslColour, LabColour, RgbColour};
+use colour_fun::colour_space::{ColourSpace, SpaceColour};
+use colour_fun::comparison::{Comparison, Metric};
+use colour_fun::contrast::{apca_contrast, contrast_ratio, wcag_level};
+use colour_fun::error_code::ErrorCode;
+use colour_fun::extraction::{dominant_colours, ExtractionOptions};
+use colour_fun::format::FormatOptions;
+use colour_fun::oklab::{OklabColour, OklchColour};
+use serde_json::{json, Map, Value};
+use std::env;
+use std::fmt;
+use std::fs::File;
+use std::process;
+
+const USAGE: &str = "usage: colour-fun [--json] <command> [arguments]
+
+commands:
+  convert <colour> [--to <format>]  hex, rgb, hsl, lab, oklab, oklch, xyz or a colour space
+  compare <colour> <colour>         distance and similarity for every metric
+  contrast <text> <background>      WCAG 2 ratio and level, and APCA lightness contrast
+  name <colour>                     nearest css colour name
+  palette <file.png> [--count <n>]  dominant colours of a PNG image";
+
+const DEFAULT_PALETTE_SIZE: usize = 5;
+
+#[derive(Debug)]
+enum CliError {
+  Usage(String),
+  Colour(ErrorCode),
+  Image(String),
+}
+
+impl fmt::Display for CliError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      CliError::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+      CliError::Colour(error) => write!(f, "{}", error),
+      CliError::Image(message) => write!(f, "{}", message),
+    }
+  }
+}
+
+impl From<ErrorCode> for CliError {
+  fn from(error: ErrorCode) -> Self {
+    CliError::Colour(error)
+  }
+}
+
+fn spaces() -> [ColourSpace; 5] {
+  [
+    ColourSpace::display_p3(),
+    ColourSpace::rec2020(),
+    ColourSpace::adobe_rgb(),
+    ColourSpace::prophoto_rgb(),
+    ColourSpace::acescg(),
+  ]
+}
+
+fn round(value: f32) -> Value {
+  json!((value as f64 * 10_000.0).round() / 10_000.0)
+}
+
+fn parse(value: Option<&String>, name: &str) -> Result<RgbColour, CliError> {
+  match value {
+    Some(value) => Ok(RgbColour::parse(value)?),
+    None => Err(CliError::Usage(format!("missing {}", name))),
+  }
+}
+
+fn convert(colour: &RgbColour, to: Option<&str>) -> Result<Value, CliError> {
+  let oklab = OklabColour::from_rgb(colour);
+  let oklch = OklchColour::from_rgb(colour);
+  let xyz = colour.to_xyz();
+
+  let mut formats = Map::new();
+  formats.insert("hex".to_string(), json!(colour.to_string()));
+  formats.insert(
+    "rgb".to_string(),
+    json!(colour.format(&FormatOptions::css())),
+  );
+  formats.insert(
+    "hsl".to_string(),
+    json!(HslColour::from(*colour).to_string()),
+  );
+  formats.insert(
+    "lab".to_string(),
+    json!(LabColour::from(*colour).to_string()),
+  );
+  formats.insert(
+    "oklab".to_string(),
+    json!(format!(
+      "oklab({} {} {})",
+      round(oklab.lightness),
+      round(oklab.a),
+      round(oklab.b)
+    )),
+  );
+  formats.insert(
+    "oklch".to_string(),
+    json!(format!(
+      "oklch({} {} {})",
+      round(oklch.lightness),
+      round(oklch.chroma),
+      round(oklch.hue)
+    )),
+  );
+  formats.insert(
+    "xyz".to_string(),
+    json!(format!(
+      "color(xyz-d65 {} {} {})",
+      round(xyz.x),
+      round(xyz.y),
+      round(xyz.z)
+    )),
+  );
+  for space in spaces().iter() {
+    formats.insert(
+      space.name().trim_start_matches("--").to_string(),
+      json!(SpaceColour::from_rgb(colour).to_space(space).to_css()),
+    );
+  }
+
+  match to {
+    None => Ok(Value::Object(formats)),
+    Some(format) => formats
+      .remove(&format.to_ascii_lowercase())
+      .map(|value| json!({ format: value }))
+      .ok_or_else(|| CliError::Usage(format!("unknown format '{}'", format))),
+  }
+}
+
+fn compare(a: &RgbColour, b: &RgbColour) -> Value {
+  let comparison = Comparison::new(a, b);
+  let mut metrics = Map::new();
+  for (name, metric) in [
+    ("rgb", Metric::Rgb),
+    ("hsl", Metric::Hsl),
+    ("lab", Metric::Lab),
+    ("ciede2000", Metric::Ciede2000),
+    ("oklab", Metric::Oklab),
+  ] {
+    let result = comparison.by(metric);
+    metrics.insert(
+      name.to_string(),
+      json!({ "distance": round(result.0), "similarity": result.1 }),
+    );
+  }
+  Value::Object(metrics)
+}
+
+fn contrast(text: &RgbColour, background: &RgbColour) -> Value {
+  json!({
+    "wcag": {
+      "ratio": round(contrast_ratio(text, background)),
+      "level": format!("{:?}", wcag_level(text, background)),
+    },
+    "apca": round(apca_contrast(text, background)),
+  })
+}
+
+fn name(colour: &RgbColour) -> Value {
+  let name = colour.nearest_colour_name();
+  let named = RgbColour::from_colour_name(&name).unwrap_or(*colour);
+  json!({
+    "name": name,
+    "hex": named.to_string(),
+    "delta_e": round(Comparison::new(colour, &named).ciede2000().0),
+  })
+}
+
+fn palette(path: &str, count: usize) -> Result<Value, CliError> {
+  let image_error = |error: &dyn fmt::Display| CliError::Image(format!("{}: {}", path, error));
+
+  let file = File::open(path).map_err(|error| image_error(&error))?;
+  let mut decoder = png::Decoder::new(file);
+  decoder.set_transformations(png::Transformations::normalize_to_color8());
+  let mut reader = decoder.read_info().map_err(|error| image_error(&error))?;
+  let mut buffer = vec![0; reader.output_buffer_size()];
+  let info = reader
+    .next_frame(&mut buffer)
+    .map_err(|error| image_error(&error))?;
+  let pixels = rgba(&buffer[..info.buffer_size()], info.color_type);
+
+  Ok(Value::Array(
+    dominant_colours(&pixels, info.width, count, &ExtractionOptions::new())
+      .iter()
+      .map(|colour| {
+        json!({
+          "hex": colour.to_string(),
+          "name": colour.nearest_colour_name(),
+        })
+      })
+      .collect(),
+  ))
+}
+
+// Expands the 8-bit output of the png decoder to the RGBA layout extraction works on.
+fn rgba(buffer: &[u8], colour_type: png::ColorType) -> Vec<u8> {
+  match colour_type {
+    png::ColorType::Rgba => buffer.to_vec(),
+    png::ColorType::Rgb => buffer
+      .chunks_exact(3)
+      .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+      .collect(),
+    png::ColorType::GrayscaleAlpha => buffer
+      .chunks_exact(2)
+      .flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]])
+      .collect(),
+    _ => buffer
+      .iter()
+      .flat_map(|&value| [value, value, value, 255])
+      .collect(),
+  }
+}
+
+fn run(arguments: &[String]) -> Result<Value, CliError> {
+  let (command, rest) = arguments
+    .split_first()
+    .ok_or_else(|| CliError::Usage("missing command".to_string()))?;
+
+  let mut positional = vec![];
+  let mut options = Map::new();
+  let mut iter = rest.iter();
+  while let Some(argument) = iter.next() {
+    match argument.strip_prefix("--") {
+      Some(option) => {
+        let value = iter
+          .next()
+          .ok_or_else(|| CliError::Usage(format!("missing value for --{}", option)))?;
+        options.insert(option.to_string(), json!(value));
+      }
+      None => positional.push(argument.clone()),
+    }
+  }
+  let option = |name: &str| options.get(name).and_then(Value::as_str);
+
+  match command.as_str() {
+    "convert" => convert(&parse(positional.first(), "colour")?, option("to")),
+    "compare" => Ok(compare(
+      &parse(positional.first(), "first colour")?,
+      &parse(positional.get(1), "second colour")?,
+    )),
+    "contrast" => Ok(contrast(
+      &parse(positional.first(), "text colour")?,
+      &parse(positional.get(1), "background colour")?,
+    )),
+    "name" => Ok(name(&parse(positional.first(), "colour")?)),
+    "palette" => {
+      let path = positional
+        .first()
+        .ok_or_else(|| CliError::Usage("missing image path".to_string()))?;
+      let count = match option("count") {
+        Some(count) => count
+          .parse()
+          .map_err(|_| CliError::Usage(format!("invalid count '{}'", count)))?,
+        None => DEFAULT_PALETTE_SIZE,
+      };
+      palette(path, count)
+    }
+    command => Err(CliError::Usage(format!("unknown command '{}'", command))),
+  }
+}
+
+// Flattens nested objects into dotted keys so every value gets its own row.
+fn rows(prefix: &str, value: &Value, rows: &mut Vec<(String, String)>) {
+  match value {
+    Value::Object(map) => {
+      for (key, value) in map {
+        let key = if prefix.is_empty() {
+          key.clone()
+        } else {
+          format!("{}.{}", prefix, key)
+        };
+        self::rows(&key, value, rows);
+      }
+    }
+    Value::Array(values) => {
+      for (index, value) in values.iter().enumerate() {
+        let key = if prefix.is_empty() {
+          (index + 1).to_string()
+        } else {
+          format!("{}.{}", prefix, index + 1)
+        };
+        self::rows(&key, value, rows);
+      }
+    }
+    Value::String(string) => rows.push((prefix.to_string(), string.clone())),
+    value => rows.push((prefix.to_string(), value.to_string())),
+  }
+}
+
+fn table(value: &Value) -> String {
+  let mut lines = vec![];
+  rows("", value, &mut lines);
+  let width = lines.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+  lines
+    .iter()
+    .map(|(key, value)| format!("{:width$}  {}", key, value, width = width))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+fn main() {
+  let mut arguments: Vec<String> = env::args().skip(1).collect();
+  let json = match arguments.iter().position(|argument| argument == "--json") {
+    Some(index) => {
+      arguments.remove(index);
+      true
+    }
+    None => false,
+  };
+
+  if arguments
+    .first()
+    .is_some_and(|argument| argument == "--help" || argument == "help")
+  {
+    println!("{}", USAGE);
+    return;
+  }
+
+  match run(&arguments) {
+    Ok(value) if json => println!("{}", serde_json::to_string_pretty(&value).unwrap()),
+    Ok(value) => println!("{}", table(&value)),
+    Err(error) => {
+      eprintln!("error: {}", error);
+      process::exit(match error {
+        CliError::Usage(_) => 2,
+        _ => 1,
+      });
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn arguments(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+  }
+
+  mod run {
+    use super::*;
+
+    #[test]
+    fn convert() {
+      let value = run(&arguments(&["convert", "rebeccapurple"])).unwrap();
+      assert_eq!(value["hex"], "#663399");
+      assert_eq!(value["rgb"], "rgb(102 51 153)");
+      assert!(value["display-p3"]
+        .as_str()
+        .unwrap()
+        .starts_with("color(display-p3"));
+
+      let value = run(&arguments(&["convert", "#fff", "--to", "hsl"])).unwrap();
+      assert_eq!(value, json!({ "hsl": "hsl(0 0% 100%)" }));
+    }
+
+    #[test]
+    fn contrast() {
+      let value = run(&arguments(&["contrast", "black", "white"])).unwrap();
+      assert_eq!(value["wcag"]["ratio"], json!(21.0));
+      assert_eq!(value["wcag"]["level"], "Aaa");
+    }
+
+    #[test]
+    fn name() {
+      let value = run(&arguments(&["name", "#fa050a"])).unwrap();
+      assert_eq!(value["name"], "red");
+      assert_eq!(value["hex"], "#ff0000");
+    }
+
+    #[test]
+    fn errors() {
+      assert!(matches!(run(&[]), Err(CliError::Usage(_))));
+      assert!(matches!(
+        run(&arguments(&["compare", "red"])),
+        Err(CliError::Usage(_))
+      ));
+      assert!(matches!(
+        run(&arguments(&["name", "rust"])),
+        Err(CliError::Colour(_))
+      ));
+    }
+  }
+
+  mod table {
+    use super::*;
+
+    #[test]
+    fn flattens() {
+      let value = json!({ "rgb": { "distance": 1.5, "similarity": 99 }, "name": "red" });
+      assert_eq!(
+        table(&value),
+        "rgb.distance    1.5\nrgb.similarity  99\nname            red"
+      );
+    }
+  }
+}
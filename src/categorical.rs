@@ -0,0 +1,298 @@
+use crate::colour::RgbColour;
+use crate::colour_space::ColourSpace;
+use crate::comparison::Metric;
+use crate::oklab::OklchColour;
+use crate::prelude::*;
+use crate::simulation::Deficiency;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const CANDIDATES: usize = 4096;
+const MAX_ATTEMPTS: usize = CANDIDATES * 32;
+const REFINEMENT_PASSES: usize = 3;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct CategoricalOptions {
+  pub metric: Metric,
+  // OKLCh lightness from 0 to 1 and chroma from 0 to about 0.37.
+  pub min_lightness: f32,
+  pub max_lightness: f32,
+  pub min_chroma: f32,
+  pub max_chroma: f32,
+  pub colour_blind_safe: bool,
+  pub seed: u64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl CategoricalOptions {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new() -> CategoricalOptions {
+    CategoricalOptions {
+      metric: Metric::Ciede2000,
+      min_lightness: 0.4,
+      max_lightness: 0.85,
+      min_chroma: 0.08,
+      max_chroma: 0.3,
+      colour_blind_safe: false,
+      seed: 0,
+    }
+  }
+}
+
+impl Default for CategoricalOptions {
+  fn default() -> Self {
+    CategoricalOptions::new()
+  }
+}
+
+// SplitMix64, so the same seed gives the same palette on every platform.
+pub(crate) struct Random(u64);
+
+impl Random {
+  pub(crate) fn new(seed: u64) -> Random {
+    Random(seed)
+  }
+
+  pub(crate) fn next_u64(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  pub(crate) fn next_f32(&mut self) -> f32 {
+    (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+  }
+
+  pub(crate) fn range(&mut self, min: f32, max: f32) -> f32 {
+    min + (max - min) * self.next_f32()
+  }
+}
+
+// Coordinates under normal vision, followed by each simulated deficiency in colour blind safe mode.
+struct Candidate {
+  colour: RgbColour,
+  views: Vec<(f32, f32, f32)>,
+}
+
+impl Candidate {
+  fn new(colour: RgbColour, options: &CategoricalOptions) -> Candidate {
+    let mut views = vec![options.metric.coordinates(colour)];
+    if options.colour_blind_safe {
+      views.extend(Deficiency::ALL.iter().map(|deficiency| {
+        options
+          .metric
+          .coordinates(colour.simulate(*deficiency, 1.0))
+      }));
+    }
+    Candidate { colour, views }
+  }
+
+  // The smallest distance across every view, so a pair has to be distinct for everyone.
+  fn distance(&self, other: &Candidate, metric: Metric) -> f32 {
+    self
+      .views
+      .iter()
+      .zip(other.views.iter())
+      .map(|(a, b)| metric.distance(a, b))
+      .fold(f32::INFINITY, f32::min)
+  }
+}
+
+fn candidates(options: &CategoricalOptions) -> Vec<Candidate> {
+  let mut random = Random::new(options.seed);
+  let srgb = ColourSpace::srgb();
+  let mut candidates = Vec::with_capacity(CANDIDATES);
+  if options.min_lightness > options.max_lightness || options.min_chroma > options.max_chroma {
+    return candidates;
+  }
+  for _ in 0..MAX_ATTEMPTS {
+    if candidates.len() >= CANDIDATES {
+      break;
+    }
+    let colour = OklchColour::new(
+      random.range(options.min_lightness, options.max_lightness),
+      random.range(options.min_chroma, options.max_chroma),
+      random.range(0.0, 360.0),
+    );
+    if colour.in_gamut(&srgb) {
+      let colour = colour.to_oklab().to_xyz().to_rgb();
+      candidates.push(Candidate::new(colour, options));
+    }
+  }
+  candidates
+}
+
+fn min_distance(candidate: &Candidate, chosen: &[Candidate], metric: Metric) -> f32 {
+  chosen
+    .iter()
+    .map(|other| candidate.distance(other, metric))
+    .fold(f32::INFINITY, f32::min)
+}
+
+// Index of the candidate furthest from everything already chosen.
+fn furthest(candidates: &[Candidate], chosen: &[Candidate], metric: Metric) -> Option<usize> {
+  candidates
+    .iter()
+    .map(|candidate| min_distance(candidate, chosen, metric))
+    .enumerate()
+    .fold(
+      None,
+      |best: Option<(usize, f32)>, (index, distance)| match best {
+        Some(best) if best.1 >= distance => Some(best),
+        _ => Some((index, distance)),
+      },
+    )
+    .map(|(index, _)| index)
+}
+
+// Greedy farthest-point sampling (Glasbey) over random in-gamut OKLCh candidates, then a few passes
+// that move each generated colour to wherever it is furthest from the rest (as iwanthue does).
+// Seed colours are kept, first and unchanged. Fewer than `count` colours come back when the ranges
+// hold too few in-gamut sRGB colours, and only the seeds when they hold none (inverted ranges, or
+// chroma outside the gamut at every allowed lightness).
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn categorical_palette(
+  count: usize,
+  seeds: Vec<RgbColour>,
+  options: &CategoricalOptions,
+) -> Vec<RgbColour> {
+  let metric = options.metric;
+  let fixed = seeds.len().min(count);
+  let mut chosen: Vec<Candidate> = seeds
+    .iter()
+    .take(count)
+    .map(|colour| Candidate::new(*colour, options))
+    .collect();
+  let mut candidates = candidates(options);
+
+  while chosen.len() < count {
+    match furthest(&candidates, &chosen, metric) {
+      Some(index) => chosen.push(candidates.swap_remove(index)),
+      None => break,
+    }
+  }
+
+  for _ in 0..REFINEMENT_PASSES {
+    for index in fixed..chosen.len() {
+      let current = chosen.remove(index);
+      let current_distance = min_distance(&current, &chosen, metric);
+      let better = furthest(&candidates, &chosen, metric).filter(|&candidate| {
+        min_distance(&candidates[candidate], &chosen, metric) > current_distance
+      });
+      let replacement = match better {
+        Some(candidate) => {
+          let replacement = candidates.swap_remove(candidate);
+          candidates.push(current);
+          replacement
+        }
+        None => current,
+      };
+      chosen.insert(index, replacement);
+    }
+  }
+
+  chosen.iter().map(|candidate| candidate.colour).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn min_pairwise(colours: &[RgbColour], metric: Metric) -> f32 {
+    let mut min = f32::INFINITY;
+    for (index, a) in colours.iter().enumerate() {
+      for b in &colours[index + 1..] {
+        min = min.min(metric.distance(&metric.coordinates(*a), &metric.coordinates(*b)));
+      }
+    }
+    min
+  }
+
+  mod categorical_palette {
+    use super::*;
+
+    #[test]
+    fn distinct() {
+      let colours = categorical_palette(8, vec![], &CategoricalOptions::new());
+      assert_eq!(colours.len(), 8);
+      assert!(min_pairwise(&colours, Metric::Ciede2000) > 15.0);
+    }
+
+    #[test]
+    fn deterministic() {
+      let options = CategoricalOptions {
+        seed: 42,
+        ..CategoricalOptions::new()
+      };
+      assert_eq!(
+        categorical_palette(6, vec![], &options),
+        categorical_palette(6, vec![], &options)
+      );
+      assert_ne!(
+        categorical_palette(6, vec![], &options),
+        categorical_palette(6, vec![], &CategoricalOptions::new())
+      );
+    }
+
+    #[test]
+    fn seeds_first() {
+      let seed = RgbColour::from_tuple(0, 82, 204);
+      let colours = categorical_palette(5, vec![seed], &CategoricalOptions::new());
+      assert_eq!(colours[0], seed);
+      assert_eq!(colours.len(), 5);
+      assert_eq!(
+        categorical_palette(1, vec![seed, seed], &CategoricalOptions::new()),
+        vec![seed]
+      );
+    }
+
+    #[test]
+    fn lightness_range() {
+      let options = CategoricalOptions {
+        metric: Metric::Oklab,
+        min_lightness: 0.7,
+        max_lightness: 0.8,
+        ..CategoricalOptions::new()
+      };
+      for colour in categorical_palette(6, vec![], &options) {
+        let lightness = Metric::Oklab.coordinates(colour).0;
+        assert!((0.69..=0.81).contains(&lightness), "{}", lightness);
+      }
+    }
+
+    #[test]
+    fn colour_blind_safe() {
+      let options = CategoricalOptions {
+        colour_blind_safe: true,
+        ..CategoricalOptions::new()
+      };
+      let colours = categorical_palette(5, vec![], &options);
+      let simulated: Vec<RgbColour> = colours
+        .iter()
+        .map(|colour| colour.simulate(Deficiency::Deuteranopia, 1.0))
+        .collect();
+      assert!(min_pairwise(&simulated, Metric::Ciede2000) > 8.0);
+    }
+
+    #[test]
+    fn no_candidates() {
+      let seed = RgbColour::from_tuple(0, 82, 204);
+      let inverted = CategoricalOptions {
+        min_lightness: 0.8,
+        max_lightness: 0.4,
+        ..CategoricalOptions::new()
+      };
+      assert_eq!(categorical_palette(4, vec![seed], &inverted), vec![seed]);
+
+      let out_of_gamut = CategoricalOptions {
+        min_chroma: 0.5,
+        max_chroma: 0.6,
+        ..CategoricalOptions::new()
+      };
+      assert!(categorical_palette(4, vec![], &out_of_gamut).is_empty());
+    }
+  }
+}
@@ -1,57 +1,243 @@
+#[cfg(feature = "alloc")]
 use crate::error_code::ErrorCode;
-use std::convert::TryInto;
+use crate::float::FloatHslColour;
+#[cfg(feature = "alloc")]
+use crate::format;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::matrix::apply;
+use crate::matrix::narrow;
+use crate::matrix::Matrix;
+use crate::matrix::Matrix64;
+#[cfg(feature = "alloc")]
+use crate::names;
+#[cfg(feature = "alloc")]
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::JsCast;
 
-#[wasm_bindgen]
-#[derive(Debug, PartialEq)]
+// Linear sRGB to and from D65 XYZ, in f64 for the floating-point types and rounded to f32 for the
+// rest.
+pub(crate) const SRGB_TO_XYZ_64: Matrix64 = [
+  [
+    0.412_390_799_265_959_5,
+    0.357_584_339_383_878,
+    0.180_480_788_401_834_3,
+  ],
+  [
+    0.212_639_005_871_510_36,
+    0.715_168_678_767_756,
+    0.072_192_315_360_733_71,
+  ],
+  [
+    0.019_330_818_715_591_82,
+    0.119_194_779_794_625_98,
+    0.950_532_152_249_660_7,
+  ],
+];
+
+pub(crate) const XYZ_TO_SRGB_64: Matrix64 = [
+  [
+    3.240_969_941_904_522_6,
+    -1.537_383_177_570_094,
+    -0.498_610_760_293_003_4,
+  ],
+  [
+    -0.969_243_636_280_879_6,
+    1.875_967_501_507_720_2,
+    0.041_555_057_407_175_59,
+  ],
+  [
+    0.055_630_079_696_993_66,
+    -0.203_976_958_888_976_52,
+    1.056_971_514_242_878_6,
+  ],
+];
+pub(crate) const SRGB_TO_XYZ: Matrix = narrow(&SRGB_TO_XYZ_64);
+pub(crate) const XYZ_TO_SRGB: Matrix = narrow(&XYZ_TO_SRGB_64);
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub struct HslColour {
   hue: u32,
   saturation: f32,
   lightness: f32,
 }
 
-impl std::convert::From<HslColour> for (f32, f32, f32) {
+impl core::convert::From<HslColour> for (f32, f32, f32) {
   fn from(colour: HslColour) -> Self {
     (colour.hue as f32, colour.saturation, colour.lightness)
   }
 }
 
-impl std::convert::From<RgbColour> for HslColour {
+impl core::convert::From<RgbColour> for HslColour {
   fn from(colour: RgbColour) -> Self {
-    colour.to_hsl().unwrap_or(HslColour {
-      hue: 0,
-      saturation: 0.0,
-      lightness: 0.0,
-    })
+    colour.hsl()
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl HslColour {
+  pub fn new(hue: u32, saturation: f32, lightness: f32) -> HslColour {
+    HslColour {
+      hue,
+      saturation,
+      lightness,
+    }
+  }
+
+  pub fn to_rgb(&self) -> RgbColour {
+    FloatHslColour::new(
+      self.hue as f64,
+      self.saturation as f64,
+      self.lightness as f64,
+    )
+    .to_rgb()
+    .to_rgb()
   }
 }
 
-#[wasm_bindgen]
-#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 pub struct LabColour {
   lightness: f32,
   a: f32,
   b: f32,
 }
 
-impl std::convert::From<LabColour> for (f32, f32, f32) {
+impl core::convert::From<LabColour> for (f32, f32, f32) {
   fn from(colour: LabColour) -> Self {
-    (colour.lightness as f32, colour.a as f32, colour.b as f32)
+    (colour.lightness, colour.a, colour.b)
   }
 }
 
-impl std::convert::From<RgbColour> for LabColour {
+impl core::convert::From<RgbColour> for LabColour {
   fn from(colour: RgbColour) -> Self {
-    colour.to_lab().unwrap_or(LabColour {
-      lightness: 0.0,
-      a: 0.0,
-      b: 0.0,
-    })
+    colour.to_xyz().to_lab()
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LabColour {
+  pub fn new(lightness: f32, a: f32, b: f32) -> LabColour {
+    LabColour { lightness, a, b }
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    fn undo_other_weird_thing(val: f32) -> f32 {
+      if val.powi(3) > 0.008856 {
+        val.powi(3)
+      } else {
+        (val - 16.0 / 116.0) / 7.787
+      }
+    }
+
+    let y = (self.lightness + 16.0) / 116.0;
+    let x = self.a / 500.0 + y;
+    let z = y - self.b / 200.0;
+
+    XyzColour {
+      x: undo_other_weird_thing(x) * 0.95047,
+      y: undo_other_weird_thing(y),
+      z: undo_other_weird_thing(z) * 1.08883,
+    }
   }
+
+  pub fn to_rgb(&self) -> RgbColour {
+    self.to_xyz().to_rgb()
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct XyzColour {
+  pub x: f32,
+  pub y: f32,
+  pub z: f32,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl XyzColour {
+  pub fn new(x: f32, y: f32, z: f32) -> XyzColour {
+    XyzColour { x, y, z }
+  }
+
+  pub fn from_chromaticity(chromaticity: &Chromaticity, luminance: f32) -> XyzColour {
+    if chromaticity.y == 0.0 {
+      return XyzColour::new(0.0, 0.0, 0.0);
+    }
+
+    XyzColour {
+      x: chromaticity.x * luminance / chromaticity.y,
+      y: luminance,
+      z: (1.0 - chromaticity.x - chromaticity.y) * luminance / chromaticity.y,
+    }
+  }
+
+  pub fn chromaticity(&self) -> Chromaticity {
+    let sum = self.x + self.y + self.z;
+    if sum == 0.0 {
+      return D65;
+    }
+
+    Chromaticity {
+      x: self.x / sum,
+      y: self.y / sum,
+    }
+  }
+
+  pub fn to_lab(&self) -> LabColour {
+    fn do_other_weird_thing(val: f32) -> f32 {
+      if val > 0.008856 {
+        val.powf(1.0 / 3.0)
+      } else {
+        7.787 * val + 16.0 / 116.0
+      }
+    }
+
+    let x = do_other_weird_thing(self.x / 0.95047);
+    let y = do_other_weird_thing(self.y / 1.0);
+    let z = do_other_weird_thing(self.z / 1.08883);
+
+    LabColour {
+      lightness: 116.0 * y - 16.0,
+      a: 500.0 * (x - y),
+      b: 200.0 * (y - z),
+    }
+  }
+
+  pub fn to_rgb(&self) -> RgbColour {
+    let (red, green, blue) = self.to_linear_rgb();
+    RgbColour::from_linear(red, green, blue)
+  }
+}
+
+impl XyzColour {
+  pub(crate) fn to_linear_rgb(self) -> (f32, f32, f32) {
+    apply(&XYZ_TO_SRGB, (self.x, self.y, self.z))
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Chromaticity {
+  pub x: f32,
+  pub y: f32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Chromaticity {
+  pub fn new(x: f32, y: f32) -> Chromaticity {
+    Chromaticity { x, y }
+  }
+}
+
+pub const D65: Chromaticity = Chromaticity {
+  x: 0.31271,
+  y: 0.32902,
+};
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub struct RgbColour {
   pub red: u8,
@@ -59,89 +245,83 @@ pub struct RgbColour {
   pub blue: u8,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl RgbColour {
   pub fn from_tuple(red: u8, green: u8, blue: u8) -> RgbColour {
     RgbColour { red, green, blue }
   }
 
-  pub fn from_hex(hex: &str) -> Result<RgbColour, JsValue> {
-    if !is_valid_hex(hex) {
-      return match hex.len() {
-        3 | 6 => Err(JsValue::from_str(&String::from(
-          ErrorCode::InvalidHexCharacter(hex.to_string()),
-        ))),
-        len => Err(JsValue::from_str(&String::from(
-          ErrorCode::InvalidHexLength(len),
-        ))),
-      };
-    }
-
-    match hex.len() {
-      3 => {
-        let chars: Vec<char> = hex.chars().collect();
-        RgbColour::from_hex(&format!(
-          "{r}{r}{g}{g}{b}{b}",
-          r = chars[0],
-          g = chars[1],
-          b = chars[2]
-        ))
-      }
-      6 => {
-        let chars: Vec<char> = hex.chars().collect();
-        let parsed = (
-          hex_pair_to_int(chars[0], chars[1]),
-          hex_pair_to_int(chars[2], chars[3]),
-          hex_pair_to_int(chars[4], chars[5]),
-        );
-        match parsed {
-          (Ok(red), Ok(green), Ok(blue)) => Ok(RgbColour { red, green, blue }),
-          _ => Err(JsValue::from_str(&String::from(
-            ErrorCode::InvalidHexCharacter(hex.to_string()),
-          ))),
+  #[cfg(feature = "alloc")]
+  pub fn from_hex(hex: &str) -> Result<RgbColour, ErrorCode> {
+    let offset = if hex.starts_with('#') { 1 } else { 0 };
+    let unprefixed = &hex[offset..];
+
+    let length = unprefixed.chars().count();
+    if length != 3 && length != 6 {
+      return Err(ErrorCode::InvalidHexLength {
+        value: hex.to_string(),
+        found: length,
+      });
+    }
+
+    let mut digits = Vec::with_capacity(length);
+    for (position, found) in unprefixed.chars().enumerate() {
+      match found.to_digit(16) {
+        Some(digit) => digits.push(digit as u8),
+        None => {
+          return Err(ErrorCode::InvalidHexCharacter {
+            value: hex.to_string(),
+            position: position + offset,
+            found,
+          })
         }
       }
-      length => Err(JsValue::from_str(&String::from(
-        ErrorCode::InvalidHexLength(length),
-      ))),
-    }
-  }
-
-  pub fn from_colour_name(colour: &str) -> Result<RgbColour, JsValue> {
-    if is_valid_colour_name(colour).is_err() | !is_valid_colour_name(colour).unwrap() {
-      return Err(JsValue::from_str(&String::from(
-        ErrorCode::InvalidColourName(colour.to_string()),
-      )));
-    }
-
-    let document = web_sys::window().unwrap().document().unwrap();
-    let canvas = document.create_element("canvas");
-    let canvas: web_sys::HtmlCanvasElement = canvas
-      .unwrap()
-      .dyn_into::<web_sys::HtmlCanvasElement>()
-      .unwrap();
-    canvas.set_height(1);
-    canvas.set_width(1);
-    let context = canvas
-      .get_context("2d")
-      .unwrap()
-      .unwrap()
-      .dyn_into::<web_sys::CanvasRenderingContext2d>()
-      .unwrap();
-    context.set_fill_style(&JsValue::from(colour));
-    context.fill_rect(0.0, 0.0, 1.0, 1.0);
-    let data = context.get_image_data(0.0, 0.0, 1.0, 1.0);
-    match data {
-      Ok(data) => {
-        let data = data.data();
-        Ok(RgbColour {
-          red: data[0],
-          green: data[1],
-          blue: data[2],
-        })
-      }
-      Err(_) => Err(JsValue::from_str(&String::from(ErrorCode::CanvasError))),
     }
+
+    Ok(match digits[..] {
+      [r, g, b] => RgbColour::from_tuple(r * 17, g * 17, b * 17),
+      _ => RgbColour::from_tuple(
+        digits[0] * 16 + digits[1],
+        digits[2] * 16 + digits[3],
+        digits[4] * 16 + digits[5],
+      ),
+    })
+  }
+
+  #[cfg(feature = "alloc")]
+  pub fn from_colour_name(colour: &str) -> Result<RgbColour, ErrorCode> {
+    names::lookup(colour).ok_or_else(|| ErrorCode::InvalidColourName(colour.to_string()))
+  }
+
+  // Accepts a hex code, a css colour name or an rgb(), hsl() or lab() function.
+  #[cfg(feature = "alloc")]
+  pub fn parse(value: &str) -> Result<RgbColour, ErrorCode> {
+    if format::is_css_function(value) {
+      return format::parse_css_function(value).map(|colour| colour.to_rgb());
+    }
+
+    let source = match RgbColour::from_hex(value) {
+      Ok(colour) => return Ok(colour),
+      Err(error) => error,
+    };
+    if let Some(colour) = names::lookup(value) {
+      return Ok(colour);
+    }
+
+    let looks_like_hex = value.starts_with('#')
+      || value.len() == 3
+      || value.len() == 6
+      || value.chars().all(|c| c.is_ascii_hexdigit());
+    let source = if looks_like_hex {
+      source
+    } else {
+      ErrorCode::InvalidColourName(value.to_string())
+    };
+
+    Err(ErrorCode::InvalidColour {
+      value: value.to_string(),
+      source: Box::new(source),
+    })
   }
 
   pub fn get_contrasting_colour(&self) -> RgbColour {
@@ -161,29 +341,44 @@ impl RgbColour {
     }
   }
 
+  #[cfg(feature = "alloc")]
   pub fn to_hex(&self) -> String {
     format!("{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
   }
 
-  pub fn to_hsl(&self) -> Result<HslColour, JsValue> {
+  pub fn to_hsl(&self) -> HslColour {
+    self.hsl()
+  }
+
+  pub fn to_lab(&self) -> LabColour {
+    self.to_xyz().to_lab()
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    let (x, y, z) = apply(&SRGB_TO_XYZ, self.to_linear());
+    XyzColour { x, y, z }
+  }
+}
+
+impl RgbColour {
+  fn hsl(self) -> HslColour {
     let red = self.red as f32 / 255.0;
     let green = self.green as f32 / 255.0;
     let blue = self.blue as f32 / 255.0;
     let mut ordered = [red, green, blue];
-    ordered.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ordered.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
 
     let min = ordered[0];
     let max = ordered[2];
     let delta = max - min;
 
     let h: f32;
-    let hue: Result<u32, std::num::TryFromIntError>;
 
     let mut lightness: f32 = (max + min) / 2.0;
     let mut saturation: f32;
 
     match delta {
-      val if val == 0.0 => {
+      0.0 => {
         h = 0.0;
         saturation = 0.0;
       }
@@ -207,115 +402,120 @@ impl RgbColour {
     saturation = (saturation * 100.0).abs();
     lightness = (lightness * 100.0).abs();
 
-    match (h * 60.0).round() as i16 {
-      val if val < 0 => {
-        hue = (val + 360).try_into();
-      }
-      val => {
-        hue = val.try_into();
-      }
+    HslColour {
+      hue: ((h * 60.0).round() as i32).rem_euclid(360) as u32,
+      saturation,
+      lightness,
     }
+  }
 
-    match hue {
-      Ok(hue) => Ok(HslColour {
-        hue,
-        saturation,
-        lightness,
-      }),
-      Err(_) => Err(JsValue::from_str(&String::from(
-        ErrorCode::HslConversionError(format!("{:?}", self)),
-      ))),
-    }
+  pub(crate) fn to_linear(self) -> (f32, f32, f32) {
+    (
+      SRGB_TO_LINEAR[self.red as usize],
+      SRGB_TO_LINEAR[self.green as usize],
+      SRGB_TO_LINEAR[self.blue as usize],
+    )
   }
 
-  pub fn to_lab(&self) -> Result<LabColour, JsValue> {
-    fn do_weird_thing(val: f32) -> f32 {
-      if val > 0.04045 {
-        ((val + 0.055) / 1.055).powf(2.4)
-      } else {
-        val / 12.92
-      }
+  pub(crate) fn from_linear(red: f32, green: f32, blue: f32) -> RgbColour {
+    fn encode(val: f32) -> u8 {
+      (linear_to_srgb(val.clamp(0.0, 1.0)) * 255.0).round() as u8
     }
 
-    fn do_other_weird_thing(val: f32) -> f32 {
-      if val > 0.008856 {
-        val.powf(1.0 / 3.0)
-      } else {
-        7.787 * val + 16.0 / 116.0
-      }
+    RgbColour {
+      red: encode(red),
+      green: encode(green),
+      blue: encode(blue),
     }
-
-    let red = do_weird_thing(self.red as f32 / 255.0);
-    let green = do_weird_thing(self.green as f32 / 255.0);
-    let blue = do_weird_thing(self.blue as f32 / 255.0);
-
-    let x = do_other_weird_thing((red * 0.4124 + green * 0.3576 + blue * 0.1805) / 0.95047);
-    let y = do_other_weird_thing((red * 0.2126 + green * 0.7152 + blue * 0.0722) / 1.0);
-    let z = do_other_weird_thing((red * 0.0193 + green * 0.1192 + blue * 0.9505) / 1.08883);
-
-    Ok(LabColour {
-      lightness: 116.0 * y - 16.0,
-      a: 500.0 * (x - y),
-      b: 200.0 * (y - z),
-    })
   }
 }
 
-impl std::convert::From<RgbColour> for (f32, f32, f32) {
-  fn from(colour: RgbColour) -> Self {
-    (colour.red as f32, colour.green as f32, colour.blue as f32)
+// libm rather than `f32::powf`, whose rounding may differ between platforms (see hash.rs).
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) fn srgb_to_linear(val: f32) -> f32 {
+  if val > 0.04045 {
+    libm::powf((val + 0.055) / 1.055, 2.4)
+  } else {
+    val / 12.92
   }
 }
 
-impl std::convert::From<&str> for RgbColour {
-  fn from(value: &str) -> RgbColour {
-    let default = RgbColour {
-      red: 0,
-      green: 0,
-      blue: 0,
-    };
-    if is_valid_hex(value) {
-      RgbColour::from_hex(value).unwrap_or(default)
-    } else if is_valid_colour_name(value).unwrap_or(false) {
-      RgbColour::from_colour_name(value).unwrap_or(default)
-    } else {
-      default
-    }
+// `srgb_to_linear` of every 8-bit level, precomputed so decoding a channel is a lookup.
+#[rustfmt::skip]
+pub(crate) const SRGB_TO_LINEAR: [f32; 256] = [
+  0.0, 0.000303527, 0.000607054, 0.000910581, 0.001214108, 0.001517635, 0.001821162,
+  0.0021246888, 0.002428216, 0.002731743, 0.00303527, 0.0033465356, 0.003676507, 0.004024717,
+  0.004391442, 0.0047769533, 0.005181517, 0.005605391, 0.0060488326, 0.006512091, 0.00699541,
+  0.0074990317, 0.008023192, 0.008568125, 0.009134057, 0.009721218, 0.010329823, 0.010960094,
+  0.011612244, 0.012286487, 0.012983031, 0.013702081, 0.014443844, 0.015208514, 0.015996292,
+  0.016807374, 0.017641952, 0.018500218, 0.019382361, 0.020288564, 0.02121901, 0.022173883,
+  0.023153365, 0.02415763, 0.025186857, 0.026241222, 0.027320892, 0.028426038, 0.029556833,
+  0.03071344, 0.03189603, 0.033104762, 0.034339808, 0.035601314, 0.036889445, 0.038204364,
+  0.039546236, 0.0409152, 0.04231141, 0.043735027, 0.045186203, 0.046665084, 0.048171822,
+  0.049706563, 0.051269464, 0.052860655, 0.054480284, 0.056128494, 0.057805434, 0.05951124,
+  0.06124607, 0.06301004, 0.06480328, 0.06662595, 0.06847818, 0.07036011, 0.07227186,
+  0.07421358, 0.07618539, 0.07818743, 0.08021983, 0.082282715, 0.084376216, 0.086500466,
+  0.088655606, 0.090841725, 0.09305898, 0.095307484, 0.09758736, 0.09989874, 0.10224175,
+  0.10461649, 0.10702311, 0.10946172, 0.111932434, 0.11443538, 0.11697067, 0.119538434,
+  0.1221388, 0.12477184, 0.1274377, 0.13013649, 0.13286833, 0.13563335, 0.13843162,
+  0.1412633, 0.14412849, 0.14702728, 0.1499598, 0.15292616, 0.15592647, 0.15896086,
+  0.1620294, 0.16513222, 0.1682694, 0.1714411, 0.17464739, 0.17788841, 0.18116423,
+  0.18447497, 0.18782076, 0.19120166, 0.19461781, 0.1980693, 0.20155624, 0.20507872,
+  0.20863685, 0.21223073, 0.21586053, 0.21952623, 0.22322798, 0.22696589, 0.23074007,
+  0.23455065, 0.23839766, 0.2422812, 0.2462014, 0.25015837, 0.25415218, 0.2581829,
+  0.26225072, 0.26635566, 0.27049786, 0.27467737, 0.27889434, 0.2831488, 0.2874409,
+  0.2917707, 0.29613832, 0.30054384, 0.30498737, 0.30946898, 0.31398875, 0.31854683,
+  0.32314324, 0.32777813, 0.33245158, 0.33716366, 0.34191445, 0.34670407, 0.3515327,
+  0.35640025, 0.36130688, 0.3662527, 0.37123775, 0.37626222, 0.3813261, 0.38642952,
+  0.39157256, 0.3967553, 0.40197787, 0.40724027, 0.4125427, 0.41788515, 0.42326772,
+  0.42869055, 0.4341537, 0.43965724, 0.44520125, 0.45078585, 0.45641106, 0.46207705,
+  0.46778384, 0.47353154, 0.47932023, 0.48514998, 0.4910209, 0.49693304, 0.5028866,
+  0.50888145, 0.5149178, 0.5209957, 0.5271152, 0.5332765, 0.5394796, 0.5457246,
+  0.5520115, 0.5583405, 0.56471163, 0.5711249, 0.5775805, 0.5840785, 0.5906189,
+  0.5972019, 0.6038274, 0.6104956, 0.61720663, 0.6239605, 0.6307572, 0.63759696,
+  0.64447975, 0.6514057, 0.6583749, 0.66538733, 0.6724432, 0.67954254, 0.68668544,
+  0.6938719, 0.701102, 0.70837593, 0.71569365, 0.72305524, 0.7304609, 0.7379105,
+  0.74540436, 0.7529423, 0.76052463, 0.7681513, 0.77582234, 0.78353786, 0.79129803,
+  0.79910284, 0.80695236, 0.81484663, 0.82278585, 0.83076996, 0.8387991, 0.8468733,
+  0.8549927, 0.8631573, 0.8713672, 0.87962234, 0.8879232, 0.89626944, 0.90466136,
+  0.9130987, 0.92158204, 0.9301109, 0.9386859, 0.9473066, 0.9559735, 0.9646863,
+  0.9734454, 0.9822506, 0.9911022, 1.0,
+];
+
+pub(crate) fn linear_to_srgb(val: f32) -> f32 {
+  if val > 0.0031308 {
+    1.055 * libm::powf(val, 1.0 / 2.4) - 0.055
+  } else {
+    val * 12.92
   }
 }
 
-impl std::convert::From<RgbColour> for String {
+impl core::convert::From<RgbColour> for (f32, f32, f32) {
   fn from(colour: RgbColour) -> Self {
-    colour.to_hex()
+    (colour.red as f32, colour.green as f32, colour.blue as f32)
   }
 }
 
-#[wasm_bindgen]
-pub fn is_valid_colour(color: &str) -> bool {
-  let is_hex = is_valid_hex(color);
-  let is_name = is_valid_colour_name(color);
-
-  match is_name {
-    Ok(is_name_value) => is_hex | is_name_value,
-    Err(_) => is_hex,
+#[cfg(feature = "alloc")]
+impl core::convert::From<RgbColour> for String {
+  fn from(colour: RgbColour) -> Self {
+    colour.to_hex()
   }
 }
 
-fn hex_pair_to_int(a: char, b: char) -> Result<u8, std::num::ParseIntError> {
-  u8::from_str_radix(&format!("{}{}", a, b), 16)
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn is_valid_colour(color: &str) -> bool {
+  RgbColour::parse(color).is_ok()
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub fn is_valid_hex(hex: &str) -> bool {
   if hex.is_empty() {
     return false;
   }
 
-  let mut unprefixed = hex;
-
-  if hex.starts_with('#') {
-    unprefixed = &hex[1..];
-  }
+  let unprefixed = hex.strip_prefix('#').unwrap_or(hex);
 
   match unprefixed.len() {
     3 | 6 => u32::from_str_radix(unprefixed, 16).is_ok(),
@@ -323,16 +523,6 @@ pub fn is_valid_hex(hex: &str) -> bool {
   }
 }
 
-fn is_valid_colour_name(colour: &str) -> Result<bool, JsValue> {
-  let o = web_sys::HtmlOptionElement::new()?;
-  let s = o.style();
-  s.set_property("color", colour)?;
-
-  println!("{:?}", s.get_property_value("color"));
-
-  Ok(s.get_property_value("color")? == colour)
-}
-
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -414,35 +604,112 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "function not implemented on non-wasm32 targets")]
     fn invalid_char() {
       assert_eq!(
         RgbColour::from_hex("F43C8X"),
-        Err(JsValue::from_str(&String::from(
-          ErrorCode::InvalidHexCharacter("F43C8X".to_string())
-        )))
+        Err(ErrorCode::InvalidHexCharacter {
+          value: "F43C8X".to_string(),
+          position: 5,
+          found: 'X'
+        })
       );
     }
 
     #[test]
-    #[should_panic(expected = "function not implemented on non-wasm32 targets")]
     fn invalid_length() {
       assert_eq!(
         RgbColour::from_hex("F43C"),
-        Err(JsValue::from_str(&String::from(
-          ErrorCode::InvalidHexLength(4)
-        )))
+        Err(ErrorCode::InvalidHexLength {
+          value: "F43C".to_string(),
+          found: 4
+        })
       );
     }
+
+    #[test]
+    fn prefixed() {
+      assert_eq!(
+        RgbColour::from_hex("#d15"),
+        Ok(RgbColour::from_tuple(221, 17, 85))
+      );
+      assert_eq!(
+        RgbColour::from_hex("#d1g"),
+        Err(ErrorCode::InvalidHexCharacter {
+          value: "#d1g".to_string(),
+          position: 3,
+          found: 'g'
+        })
+      );
+    }
+  }
+
+  mod rgb_from_colour_name {
+    use super::*;
+
+    #[test]
+    fn valid() {
+      assert_eq!(
+        RgbColour::from_colour_name("pink"),
+        Ok(RgbColour::from_tuple(255, 192, 203))
+      );
+      assert!(is_valid_colour("yellowgreen"));
+    }
+
+    #[test]
+    fn invalid() {
+      assert_eq!(
+        RgbColour::from_colour_name("rust"),
+        Err(ErrorCode::InvalidColourName("rust".to_string()))
+      );
+      assert!(!is_valid_colour("rust"));
+    }
   }
 
-  mod rgb_from_string {
+  mod rgb_parse {
+    use super::*;
+
+    #[test]
+    fn hex_and_name() {
+      assert_eq!(
+        RgbColour::parse("#663399"),
+        RgbColour::parse("rebeccapurple")
+      );
+    }
+
+    #[test]
+    fn invalid_hex() {
+      assert_eq!(
+        RgbColour::parse("F43C8X"),
+        Err(ErrorCode::InvalidColour {
+          value: "F43C8X".to_string(),
+          source: Box::new(ErrorCode::InvalidHexCharacter {
+            value: "F43C8X".to_string(),
+            position: 5,
+            found: 'X'
+          })
+        })
+      );
+    }
+
+    #[test]
+    fn invalid_name() {
+      assert_eq!(
+        RgbColour::parse("rust"),
+        Err(ErrorCode::InvalidColour {
+          value: "rust".to_string(),
+          source: Box::new(ErrorCode::InvalidColourName("rust".to_string()))
+        })
+      );
+    }
+  }
+
+  mod rgb_from_str_lossy {
     use super::*;
 
     #[test]
     fn six_char() {
       assert_eq!(
-        RgbColour::from("F43C8E"),
+        RgbColour::from_str_lossy("F43C8E"),
         RgbColour {
           red: 244,
           green: 60,
@@ -454,7 +721,7 @@ mod tests {
     #[test]
     fn three_char() {
       assert_eq!(
-        RgbColour::from("d15"),
+        RgbColour::from_str_lossy("d15"),
         RgbColour {
           red: 221,
           green: 17,
@@ -464,17 +731,27 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "cannot call wasm-bindgen imported functions on non-wasm targets")]
+    fn colour_name() {
+      assert_eq!(
+        RgbColour::from_str_lossy("RebeccaPurple"),
+        RgbColour::from_tuple(102, 51, 153)
+      );
+    }
+
+    #[test]
     fn invalid_char() {
-      // should panic because converter will attempt to parse as css colour name
-      RgbColour::from("F43C8X");
+      assert_eq!(
+        RgbColour::from_str_lossy("F43C8X"),
+        RgbColour::from_tuple(0, 0, 0)
+      );
     }
 
     #[test]
-    #[should_panic(expected = "cannot call wasm-bindgen imported functions on non-wasm targets")]
     fn invalid_length() {
-      // should panic because converter will attempt to parse as css colour name
-      RgbColour::from("F43C");
+      assert_eq!(
+        RgbColour::from_str_lossy("F43C"),
+        RgbColour::from_tuple(0, 0, 0)
+      );
     }
   }
 
@@ -500,7 +777,7 @@ mod tests {
     #[test]
     fn rebeccapurple() {
       assert_eq!(
-        HslColour::from(RgbColour::from("663399")),
+        HslColour::from(RgbColour::from_str_lossy("663399")),
         HslColour {
           hue: 270,
           saturation: 50.000008,
@@ -509,6 +786,13 @@ mod tests {
       )
     }
 
+    #[test]
+    fn wraps_negative_hue() {
+      let hsl = HslColour::from(RgbColour::from_tuple(255, 0, 5));
+      assert_eq!(hsl.hue, 359);
+      assert_eq!(hsl.lightness, 50.0);
+    }
+
     #[test]
     fn white() {
       assert_eq!(
@@ -554,9 +838,9 @@ mod tests {
           blue: 32
         }),
         LabColour {
-          lightness: 53.020706,
-          a: 72.232574,
-          b: 55.97896
+          lightness: 53.024467,
+          a: 72.21347,
+          b: 55.968052
         }
       )
     }
@@ -564,11 +848,11 @@ mod tests {
     #[test]
     fn rebeccapurple() {
       assert_eq!(
-        LabColour::from(RgbColour::from("663399")),
+        LabColour::from(RgbColour::from_str_lossy("663399")),
         LabColour {
-          lightness: 32.902435,
-          a: 42.89223,
-          b: -47.156937
+          lightness: 32.902805,
+          a: 42.885273,
+          b: -47.158302
         }
       )
     }
@@ -583,8 +867,8 @@ mod tests {
         }),
         LabColour {
           lightness: 100.0,
-          a: 0.0052452087,
-          b: -0.010418892
+          a: -0.0024735928,
+          b: -0.013947487
         }
       )
     }
@@ -606,13 +890,62 @@ mod tests {
     }
   }
 
+  mod xyz {
+    use super::*;
+
+    #[test]
+    fn white_is_d65() {
+      let chromaticity = RgbColour::from_tuple(255, 255, 255).to_xyz().chromaticity();
+      assert!((chromaticity.x - D65.x).abs() < 0.0001);
+      assert!((chromaticity.y - D65.y).abs() < 0.0001);
+    }
+
+    #[test]
+    fn rgb_round_trip() {
+      let colour = RgbColour::from_tuple(244, 43, 32);
+      assert_eq!(colour.to_xyz().to_rgb(), colour);
+    }
+
+    #[test]
+    fn lab_round_trip() {
+      for colour in [
+        RgbColour::from_tuple(244, 43, 32),
+        RgbColour::from_tuple(102, 51, 153),
+        RgbColour::from_tuple(3, 4, 5),
+      ]
+      .iter()
+      {
+        assert_eq!(LabColour::from(*colour).to_rgb(), *colour);
+      }
+    }
+
+    #[test]
+    fn from_chromaticity() {
+      let xyz = XyzColour::from_chromaticity(&D65, 1.0);
+      assert!((xyz.x - 0.95043).abs() < 0.0001);
+      assert_eq!(xyz.y, 1.0);
+      assert!((xyz.z - 1.0889).abs() < 0.0001);
+    }
+  }
+
+  mod srgb_to_linear {
+    use super::*;
+
+    #[test]
+    fn table_matches_transfer_function() {
+      for (level, value) in SRGB_TO_LINEAR.iter().enumerate() {
+        assert_eq!(*value, srgb_to_linear(level as f32 / 255.0), "{}", level);
+      }
+    }
+  }
+
   mod rgb_contrasting_colour {
     use super::*;
 
     #[test]
     fn dark_colour() {
       assert_eq!(
-        RgbColour::from("054").get_contrasting_colour(),
+        RgbColour::from_str_lossy("054").get_contrasting_colour(),
         RgbColour {
           red: 255,
           green: 255,
@@ -624,7 +957,7 @@ mod tests {
     #[test]
     fn light_colour() {
       assert_eq!(
-        RgbColour::from("f54").get_contrasting_colour(),
+        RgbColour::from_str_lossy("f54").get_contrasting_colour(),
         RgbColour {
           red: 0,
           green: 0,
@@ -0,0 +1,545 @@
+use crate::colour::Chromaticity;
+use crate::colour::RgbColour;
+use crate::colour::XyzColour;
+use crate::colour::D65;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::matrix::apply;
+use crate::matrix::invert;
+use crate::matrix::multiply;
+use crate::matrix::Matrix;
+use crate::prelude::*;
+use crate::tokens::slug;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Spaces `color()` accepts by name; anything else has to be a `--dashed-ident` profile.
+const CSS_SPACES: [&str; 9] = [
+  "srgb",
+  "srgb-linear",
+  "display-p3",
+  "a98-rgb",
+  "prophoto-rgb",
+  "rec2020",
+  "xyz",
+  "xyz-d50",
+  "xyz-d65",
+];
+
+pub const D50: Chromaticity = Chromaticity {
+  x: 0.3457,
+  y: 0.3585,
+};
+
+pub const ACES_WHITE: Chromaticity = Chromaticity {
+  x: 0.32168,
+  y: 0.33767,
+};
+
+const BRADFORD: Matrix = [
+  [0.8951, 0.2664, -0.1614],
+  [-0.7502, 1.7135, 0.0367],
+  [0.0389, -0.0685, 1.0296],
+];
+
+// Rec. 2020 transfer function constants.
+const REC2020_ALPHA: f32 = 1.099_296_8;
+const REC2020_BETA: f32 = 0.018_053_97;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum TransferFunction {
+  Linear,
+  Srgb,
+  Rec2020,
+  ProPhoto,
+  Gamma(f32),
+}
+
+impl TransferFunction {
+  // Negative values are mirrored so that out of gamut colours survive a round trip.
+  fn mirrored(value: f32, curve: impl Fn(f32) -> f32) -> f32 {
+    value.signum() * curve(value.abs())
+  }
+
+  pub fn decode(self, value: f32) -> f32 {
+    match self {
+      TransferFunction::Linear => value,
+      TransferFunction::Srgb => TransferFunction::mirrored(value, crate::colour::srgb_to_linear),
+      TransferFunction::Rec2020 => TransferFunction::mirrored(value, |val| {
+        if val < REC2020_BETA * 4.5 {
+          val / 4.5
+        } else {
+          ((val + REC2020_ALPHA - 1.0) / REC2020_ALPHA).powf(1.0 / 0.45)
+        }
+      }),
+      TransferFunction::ProPhoto => TransferFunction::mirrored(value, |val| {
+        if val < 16.0 / 512.0 {
+          val / 16.0
+        } else {
+          val.powf(1.8)
+        }
+      }),
+      TransferFunction::Gamma(gamma) => TransferFunction::mirrored(value, |val| val.powf(gamma)),
+    }
+  }
+
+  pub fn encode(self, value: f32) -> f32 {
+    match self {
+      TransferFunction::Linear => value,
+      TransferFunction::Srgb => TransferFunction::mirrored(value, crate::colour::linear_to_srgb),
+      TransferFunction::Rec2020 => TransferFunction::mirrored(value, |val| {
+        if val < REC2020_BETA {
+          val * 4.5
+        } else {
+          REC2020_ALPHA * val.powf(0.45) - (REC2020_ALPHA - 1.0)
+        }
+      }),
+      TransferFunction::ProPhoto => TransferFunction::mirrored(value, |val| {
+        if val < 1.0 / 512.0 {
+          val * 16.0
+        } else {
+          val.powf(1.0 / 1.8)
+        }
+      }),
+      TransferFunction::Gamma(gamma) => {
+        TransferFunction::mirrored(value, |val| val.powf(1.0 / gamma))
+      }
+    }
+  }
+}
+
+// Bradford chromatic adaptation from one white point to another.
+pub(crate) fn adaptation(from: &Chromaticity, to: &Chromaticity) -> Matrix {
+  let from_xyz = XyzColour::from_chromaticity(from, 1.0);
+  let to_xyz = XyzColour::from_chromaticity(to, 1.0);
+  let from_cone = apply(&BRADFORD, (from_xyz.x, from_xyz.y, from_xyz.z));
+  let to_cone = apply(&BRADFORD, (to_xyz.x, to_xyz.y, to_xyz.z));
+  let scale = [
+    [to_cone.0 / from_cone.0, 0.0, 0.0],
+    [0.0, to_cone.1 / from_cone.1, 0.0],
+    [0.0, 0.0, to_cone.2 / from_cone.2],
+  ];
+  multiply(&invert(&BRADFORD), &multiply(&scale, &BRADFORD))
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct ColourSpace {
+  name: String,
+  red: Chromaticity,
+  green: Chromaticity,
+  blue: Chromaticity,
+  white: Chromaticity,
+  transfer: TransferFunction,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ColourSpace {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(
+    name: &str,
+    red: &Chromaticity,
+    green: &Chromaticity,
+    blue: &Chromaticity,
+    white: &Chromaticity,
+    gamma: f32,
+  ) -> ColourSpace {
+    ColourSpace::custom(
+      name,
+      *red,
+      *green,
+      *blue,
+      *white,
+      TransferFunction::Gamma(gamma),
+    )
+  }
+
+  pub fn srgb() -> ColourSpace {
+    ColourSpace::custom(
+      "srgb",
+      Chromaticity::new(0.64, 0.33),
+      Chromaticity::new(0.30, 0.60),
+      Chromaticity::new(0.15, 0.06),
+      D65,
+      TransferFunction::Srgb,
+    )
+  }
+
+  pub fn display_p3() -> ColourSpace {
+    ColourSpace::custom(
+      "display-p3",
+      Chromaticity::new(0.680, 0.320),
+      Chromaticity::new(0.265, 0.690),
+      Chromaticity::new(0.150, 0.060),
+      D65,
+      TransferFunction::Srgb,
+    )
+  }
+
+  pub fn rec2020() -> ColourSpace {
+    ColourSpace::custom(
+      "rec2020",
+      Chromaticity::new(0.708, 0.292),
+      Chromaticity::new(0.170, 0.797),
+      Chromaticity::new(0.131, 0.046),
+      D65,
+      TransferFunction::Rec2020,
+    )
+  }
+
+  pub fn adobe_rgb() -> ColourSpace {
+    ColourSpace::custom(
+      "a98-rgb",
+      Chromaticity::new(0.64, 0.33),
+      Chromaticity::new(0.21, 0.71),
+      Chromaticity::new(0.15, 0.06),
+      D65,
+      TransferFunction::Gamma(563.0 / 256.0),
+    )
+  }
+
+  pub fn prophoto_rgb() -> ColourSpace {
+    ColourSpace::custom(
+      "prophoto-rgb",
+      Chromaticity::new(0.734_699, 0.265_301),
+      Chromaticity::new(0.159_597, 0.840_403),
+      Chromaticity::new(0.036_598, 0.000_105),
+      D50,
+      TransferFunction::ProPhoto,
+    )
+  }
+
+  pub fn acescg() -> ColourSpace {
+    ColourSpace::custom(
+      "--acescg",
+      Chromaticity::new(0.713, 0.293),
+      Chromaticity::new(0.165, 0.830),
+      Chromaticity::new(0.128, 0.044),
+      ACES_WHITE,
+      TransferFunction::Linear,
+    )
+  }
+
+  pub fn name(&self) -> String {
+    self.name.clone()
+  }
+
+  pub fn white(&self) -> Chromaticity {
+    self.white
+  }
+
+  pub fn to_xyz(&self, red: f32, green: f32, blue: f32) -> XyzColour {
+    let linear = (
+      self.transfer.decode(red),
+      self.transfer.decode(green),
+      self.transfer.decode(blue),
+    );
+    let (x, y, z) = apply(&self.to_xyz_matrix(), linear);
+    XyzColour::new(x, y, z)
+  }
+
+  pub fn from_xyz(&self, xyz: &XyzColour) -> SpaceColour {
+    let (red, green, blue) = self.xyz_to_linear(xyz);
+    SpaceColour::new(
+      self,
+      self.transfer.encode(red),
+      self.transfer.encode(green),
+      self.transfer.encode(blue),
+    )
+  }
+}
+
+impl ColourSpace {
+  pub fn custom(
+    name: &str,
+    red: Chromaticity,
+    green: Chromaticity,
+    blue: Chromaticity,
+    white: Chromaticity,
+    transfer: TransferFunction,
+  ) -> ColourSpace {
+    ColourSpace {
+      name: name.to_string(),
+      red,
+      green,
+      blue,
+      white,
+      transfer,
+    }
+  }
+
+  pub fn transfer(&self) -> TransferFunction {
+    self.transfer
+  }
+
+  // Linear RGB to D65-relative XYZ, adapting from the space's own white point.
+  pub(crate) fn to_xyz_matrix(&self) -> Matrix {
+    let primary = |chromaticity: &Chromaticity| XyzColour::from_chromaticity(chromaticity, 1.0);
+    let (red, green, blue) = (
+      primary(&self.red),
+      primary(&self.green),
+      primary(&self.blue),
+    );
+    let primaries = [
+      [red.x, green.x, blue.x],
+      [red.y, green.y, blue.y],
+      [red.z, green.z, blue.z],
+    ];
+    let white = XyzColour::from_chromaticity(&self.white, 1.0);
+    let scale = apply(&invert(&primaries), (white.x, white.y, white.z));
+    let matrix = [
+      [
+        primaries[0][0] * scale.0,
+        primaries[0][1] * scale.1,
+        primaries[0][2] * scale.2,
+      ],
+      [
+        primaries[1][0] * scale.0,
+        primaries[1][1] * scale.1,
+        primaries[1][2] * scale.2,
+      ],
+      [
+        primaries[2][0] * scale.0,
+        primaries[2][1] * scale.1,
+        primaries[2][2] * scale.2,
+      ],
+    ];
+
+    if self.white == D65 {
+      matrix
+    } else {
+      multiply(&adaptation(&self.white, &D65), &matrix)
+    }
+  }
+
+  pub(crate) fn xyz_to_linear(&self, xyz: &XyzColour) -> (f32, f32, f32) {
+    apply(&invert(&self.to_xyz_matrix()), (xyz.x, xyz.y, xyz.z))
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct SpaceColour {
+  pub(crate) space: ColourSpace,
+  pub red: f32,
+  pub green: f32,
+  pub blue: f32,
+}
+
+// Rounds to `decimals` places and drops trailing zeros, so float noise doesn't reach the output.
+pub(crate) fn format_number(value: f32, decimals: usize) -> String {
+  let formatted = format!("{:.*}", decimals, value);
+  let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+  match trimmed {
+    "-0" | "" => "0".to_string(),
+    value => value.to_string(),
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl SpaceColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(space: &ColourSpace, red: f32, green: f32, blue: f32) -> SpaceColour {
+    SpaceColour {
+      space: space.clone(),
+      red,
+      green,
+      blue,
+    }
+  }
+
+  pub fn from_rgb(colour: &RgbColour) -> SpaceColour {
+    SpaceColour::new(
+      &ColourSpace::srgb(),
+      colour.red as f32 / 255.0,
+      colour.green as f32 / 255.0,
+      colour.blue as f32 / 255.0,
+    )
+  }
+
+  pub fn space(&self) -> ColourSpace {
+    self.space.clone()
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    self.space.to_xyz(self.red, self.green, self.blue)
+  }
+
+  pub fn to_space(&self, space: &ColourSpace) -> SpaceColour {
+    space.from_xyz(&self.to_xyz())
+  }
+
+  // Converts to 8-bit sRGB, clamping anything outside the sRGB gamut.
+  pub fn to_rgb(&self) -> RgbColour {
+    let srgb = self.to_space(&ColourSpace::srgb());
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    RgbColour::from_tuple(channel(srgb.red), channel(srgb.green), channel(srgb.blue))
+  }
+
+  // Custom spaces are written as a `--dashed-ident` made from their name, for use with an
+  // `@color-profile` rule.
+  pub fn to_css(&self) -> String {
+    let name = if CSS_SPACES.contains(&self.space.name.as_str()) {
+      self.space.name.clone()
+    } else {
+      match slug(&self.space.name, '-') {
+        slug if slug.is_empty() => "--custom".to_string(),
+        slug => format!("--{}", slug),
+      }
+    };
+    format!(
+      "color({} {} {} {})",
+      name,
+      format_number(self.red, 5),
+      format_number(self.green, 5),
+      format_number(self.blue, 5)
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_channels(colour: &SpaceColour, expected: (f32, f32, f32)) {
+    let actual = (colour.red, colour.green, colour.blue);
+    assert!(
+      (actual.0 - expected.0).abs() < 0.0005
+        && (actual.1 - expected.1).abs() < 0.0005
+        && (actual.2 - expected.2).abs() < 0.0005,
+      "{:?} != {:?}",
+      actual,
+      expected
+    );
+  }
+
+  mod transfer_function {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+      for transfer in [
+        TransferFunction::Linear,
+        TransferFunction::Srgb,
+        TransferFunction::Rec2020,
+        TransferFunction::ProPhoto,
+        TransferFunction::Gamma(2.2),
+      ]
+      .iter()
+      {
+        for &value in &[-0.5, 0.0, 0.001, 0.2, 0.5, 1.0, 1.3] {
+          let round_trip = transfer.encode(transfer.decode(value));
+          assert!(
+            (round_trip - value).abs() < 0.0001,
+            "{:?} {}",
+            transfer,
+            value
+          );
+        }
+      }
+    }
+  }
+
+  mod srgb {
+    use super::*;
+
+    #[test]
+    fn matrix_matches_srgb_specification() {
+      let matrix = ColourSpace::srgb().to_xyz_matrix();
+      assert!((matrix[0][0] - 0.4124).abs() < 0.0005);
+      assert!((matrix[1][1] - 0.7152).abs() < 0.0005);
+      assert!((matrix[2][2] - 0.9505).abs() < 0.0005);
+    }
+
+    #[test]
+    fn matches_rgb_xyz() {
+      let colour = RgbColour::from_tuple(244, 43, 32);
+      let xyz = SpaceColour::from_rgb(&colour).to_xyz();
+      let expected = colour.to_xyz();
+      assert!((xyz.x - expected.x).abs() < 0.0005);
+      assert!((xyz.y - expected.y).abs() < 0.0005);
+      assert!((xyz.z - expected.z).abs() < 0.0005);
+    }
+  }
+
+  mod to_space {
+    use super::*;
+
+    #[test]
+    fn white_is_white_everywhere() {
+      let white = SpaceColour::from_rgb(&RgbColour::from_tuple(255, 255, 255));
+      for space in [
+        ColourSpace::display_p3(),
+        ColourSpace::rec2020(),
+        ColourSpace::adobe_rgb(),
+        ColourSpace::prophoto_rgb(),
+        ColourSpace::acescg(),
+      ]
+      .iter()
+      {
+        assert_channels(&white.to_space(space), (1.0, 1.0, 1.0));
+      }
+    }
+
+    #[test]
+    fn srgb_red_in_display_p3() {
+      let red = SpaceColour::from_rgb(&RgbColour::from_tuple(255, 0, 0));
+      assert_channels(
+        &red.to_space(&ColourSpace::display_p3()),
+        (0.9175, 0.2003, 0.1386),
+      );
+    }
+
+    #[test]
+    fn display_p3_red_is_outside_srgb() {
+      let red = SpaceColour::new(&ColourSpace::display_p3(), 1.0, 0.0, 0.0);
+      assert_channels(
+        &red.to_space(&ColourSpace::srgb()),
+        (1.0931, -0.2267, -0.1501),
+      );
+      assert_eq!(red.to_rgb(), RgbColour::from_tuple(255, 0, 0));
+    }
+
+    #[test]
+    fn round_trip_through_prophoto() {
+      let colour = SpaceColour::new(&ColourSpace::display_p3(), 0.3, 0.6, 0.9);
+      let round_trip = colour
+        .to_space(&ColourSpace::prophoto_rgb())
+        .to_space(&ColourSpace::display_p3());
+      assert_channels(&round_trip, (0.3, 0.6, 0.9));
+    }
+  }
+
+  mod to_css {
+    use super::*;
+
+    #[test]
+    fn display_p3() {
+      let colour = SpaceColour::new(&ColourSpace::display_p3(), 1.0, 0.5, 0.123456);
+      assert_eq!(colour.to_css(), "color(display-p3 1 0.5 0.12346)");
+    }
+
+    #[test]
+    fn negative_zero() {
+      let colour = SpaceColour::new(&ColourSpace::rec2020(), -0.0, 0.0, 0.25);
+      assert_eq!(colour.to_css(), "color(rec2020 0 0 0.25)");
+    }
+
+    #[test]
+    fn custom_spaces() {
+      let acescg = SpaceColour::new(&ColourSpace::acescg(), 0.5, 0.5, 0.5);
+      assert_eq!(acescg.to_css(), "color(--acescg 0.5 0.5 0.5)");
+
+      let space = ColourSpace::new(
+        "My Space",
+        &Chromaticity::new(0.64, 0.33),
+        &Chromaticity::new(0.30, 0.60),
+        &Chromaticity::new(0.15, 0.06),
+        &D65,
+        2.2,
+      );
+      let colour = SpaceColour::new(&space, 1.0, 0.0, 0.0);
+      assert_eq!(colour.to_css(), "color(--my-space 1 0 0)");
+    }
+  }
+}
@@ -1,20 +1,58 @@
 use crate::colour::HslColour;
 use crate::colour::LabColour;
 use crate::colour::RgbColour;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::oklab::OklabColour;
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComparisonResult(pub f32, pub i32);
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Metric {
+  Rgb,
+  Hsl,
+  Lab,
+  Ciede2000,
+  Oklab,
+}
+
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+impl Metric {
+  pub(crate) fn coordinates(self, colour: RgbColour) -> (f32, f32, f32) {
+    match self {
+      Metric::Rgb => colour.into(),
+      Metric::Hsl => HslColour::from(colour).into(),
+      Metric::Lab | Metric::Ciede2000 => LabColour::from(colour).into(),
+      Metric::Oklab => {
+        let oklab = OklabColour::from_rgb(&colour);
+        (oklab.lightness, oklab.a, oklab.b)
+      }
+    }
+  }
+
+  pub(crate) fn distance(self, a: &(f32, f32, f32), b: &(f32, f32, f32)) -> f32 {
+    match self {
+      Metric::Rgb | Metric::Hsl | Metric::Oklab => Comparison::euclidian_distance(a, b),
+      Metric::Lab => Comparison::delta_e(a, b),
+      Metric::Ciede2000 => Comparison::ciede2000_distance(a, b),
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 #[derive(Debug, PartialEq)]
 pub struct Comparison {
   a: RgbColour,
   b: RgbColour,
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 impl Comparison {
   pub fn new(a: &RgbColour, b: &RgbColour) -> Comparison {
     Comparison { a: *a, b: *b }
@@ -89,6 +127,62 @@ impl Comparison {
     ComparisonResult(actual, percentage)
   }
 
+  pub fn ciede2000(&self) -> ComparisonResult {
+    let max = Comparison::ciede2000_distance(
+      &LabColour::from(RgbColour {
+        red: 255,
+        green: 255,
+        blue: 255,
+      })
+      .into(),
+      &LabColour::from(RgbColour {
+        red: 0,
+        green: 0,
+        blue: 0,
+      })
+      .into(),
+    );
+    let actual = Comparison::ciede2000_distance(
+      &LabColour::from(self.a).into(),
+      &LabColour::from(self.b).into(),
+    );
+    let percentage = Comparison::calculate_percentage(actual, max);
+
+    ComparisonResult(actual, percentage)
+  }
+
+  pub fn oklab(&self) -> ComparisonResult {
+    let max = Comparison::euclidian_distance(
+      &Metric::Oklab.coordinates(RgbColour {
+        red: 255,
+        green: 255,
+        blue: 255,
+      }),
+      &Metric::Oklab.coordinates(RgbColour {
+        red: 0,
+        green: 0,
+        blue: 0,
+      }),
+    );
+    let actual = Comparison::euclidian_distance(
+      &Metric::Oklab.coordinates(self.a),
+      &Metric::Oklab.coordinates(self.b),
+    );
+    let percentage = Comparison::calculate_percentage(actual, max);
+
+    ComparisonResult(actual, percentage)
+  }
+
+  pub fn by(&self, metric: Metric) -> ComparisonResult {
+    match metric {
+      Metric::Rgb => self.rgb(),
+      Metric::Hsl => self.hsl(),
+      Metric::Lab => self.lab(),
+      Metric::Ciede2000 => self.ciede2000(),
+      Metric::Oklab => self.oklab(),
+    }
+  }
+
   fn calculate_percentage(actual: f32, max: f32) -> i32 {
     (100.0 - actual * (100.0 / max)).floor() as i32
   }
@@ -126,12 +220,106 @@ impl Comparison {
       result.sqrt()
     }
   }
+
+  // Sharma, Wu & Dalal, "The CIEDE2000 color-difference formula" (2005).
+  fn ciede2000_distance(x: &(f32, f32, f32), y: &(f32, f32, f32)) -> f32 {
+    fn hue_angle(b: f32, a: f32) -> f32 {
+      if a == 0.0 && b == 0.0 {
+        0.0
+      } else {
+        b.atan2(a).to_degrees().rem_euclid(360.0)
+      }
+    }
+
+    let pow_25_7 = 25.0_f32.powi(7);
+    let c_x = (x.1 * x.1 + x.2 * x.2).sqrt();
+    let c_y = (y.1 * y.1 + y.2 * y.2).sqrt();
+    let c_mean_7 = ((c_x + c_y) / 2.0).powi(7);
+    let g = 0.5 * (1.0 - (c_mean_7 / (c_mean_7 + pow_25_7)).sqrt());
+
+    let a_x = (1.0 + g) * x.1;
+    let a_y = (1.0 + g) * y.1;
+    let c_x = (a_x * a_x + x.2 * x.2).sqrt();
+    let c_y = (a_y * a_y + y.2 * y.2).sqrt();
+    let h_x = hue_angle(x.2, a_x);
+    let h_y = hue_angle(y.2, a_y);
+
+    let delta_l = y.0 - x.0;
+    let delta_c = c_y - c_x;
+    let delta_h = if c_x * c_y == 0.0 {
+      0.0
+    } else {
+      match h_y - h_x {
+        val if val > 180.0 => val - 360.0,
+        val if val < -180.0 => val + 360.0,
+        val => val,
+      }
+    };
+    let delta_h = 2.0 * (c_x * c_y).sqrt() * (delta_h / 2.0).to_radians().sin();
+
+    let l_mean = (x.0 + y.0) / 2.0;
+    let c_mean = (c_x + c_y) / 2.0;
+    let h_mean = if c_x * c_y == 0.0 {
+      h_x + h_y
+    } else if (h_x - h_y).abs() <= 180.0 {
+      (h_x + h_y) / 2.0
+    } else if h_x + h_y < 360.0 {
+      (h_x + h_y + 360.0) / 2.0
+    } else {
+      (h_x + h_y - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (h_mean - 30.0).to_radians().cos()
+      + 0.24 * (2.0 * h_mean).to_radians().cos()
+      + 0.32 * (3.0 * h_mean + 6.0).to_radians().cos()
+      - 0.20 * (4.0 * h_mean - 63.0).to_radians().cos();
+    let delta_theta = 30.0 * (-((h_mean - 275.0) / 25.0).powi(2)).exp();
+    let c_mean_7 = c_mean.powi(7);
+    let r_c = 2.0 * (c_mean_7 / (c_mean_7 + pow_25_7)).sqrt();
+    let l_offset = (l_mean - 50.0) * (l_mean - 50.0);
+    let s_l = 1.0 + 0.015 * l_offset / (20.0 + l_offset).sqrt();
+    let s_c = 1.0 + 0.045 * c_mean;
+    let s_h = 1.0 + 0.015 * c_mean * t;
+    let r_t = -(2.0 * delta_theta).to_radians().sin() * r_c;
+
+    let l_term = delta_l / s_l;
+    let c_term = delta_c / s_c;
+    let h_term = delta_h / s_h;
+    (l_term * l_term + c_term * c_term + h_term * h_term + r_t * c_term * h_term)
+      .max(0.0)
+      .sqrt()
+  }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
 
+  mod by {
+    use super::*;
+
+    #[test]
+    fn matches_metric_method() {
+      let comparison = Comparison::new(
+        &RgbColour {
+          red: 65,
+          green: 123,
+          blue: 165,
+        },
+        &RgbColour {
+          red: 87,
+          green: 87,
+          blue: 65,
+        },
+      );
+      assert_eq!(comparison.by(Metric::Rgb), comparison.rgb());
+      assert_eq!(comparison.by(Metric::Hsl), comparison.hsl());
+      assert_eq!(comparison.by(Metric::Lab), comparison.lab());
+      assert_eq!(comparison.by(Metric::Ciede2000), comparison.ciede2000());
+      assert_eq!(comparison.by(Metric::Oklab), comparison.oklab());
+    }
+  }
+
   mod rgb {
     use super::*;
 
@@ -239,7 +427,7 @@ mod tests {
           }
         )
         .lab(),
-        ComparisonResult(2.8480887, 97)
+        ComparisonResult(2.8484197, 97)
       )
     }
 
@@ -259,8 +447,81 @@ mod tests {
           }
         )
         .lab(),
-        ComparisonResult(30.377132, 69)
+        ComparisonResult(30.376122, 69)
+      )
+    }
+  }
+
+  mod ciede2000 {
+    use super::*;
+
+    // Reference pairs from Sharma, Wu & Dalal's CIEDE2000 test data.
+    #[test]
+    fn reference_data() {
+      let pairs = [
+        ((50.0, 2.6772, -79.7751), (50.0, 0.0, -82.7485), 2.0425),
+        ((50.0, -1.3802, -84.2814), (50.0, 0.0, -82.7485), 1.0),
+        ((50.0, 2.5, 0.0), (73.0, 25.0, -18.0), 27.1492),
+        ((50.0, 2.5, 0.0), (50.0, 0.0, -2.5), 4.3065),
+        (
+          (22.7233, 20.0904, -46.694),
+          (23.0331, 14.973, -42.5619),
+          2.0373,
+        ),
+      ];
+      for (x, y, expected) in pairs.iter() {
+        let actual = Comparison::ciede2000_distance(x, y);
+        assert!(
+          (actual - expected).abs() < 0.001,
+          "{} != {}",
+          actual,
+          expected
+        );
+      }
+    }
+
+    #[test]
+    fn comparison_1() {
+      assert_eq!(
+        Comparison::new(
+          &RgbColour {
+            red: 3,
+            green: 43,
+            blue: 234
+          },
+          &RgbColour {
+            red: 43,
+            green: 54,
+            blue: 231
+          }
+        )
+        .ciede2000()
+        .1,
+        97
+      )
+    }
+  }
+
+  mod oklab {
+    use super::*;
+
+    #[test]
+    fn black_and_white() {
+      let result = Comparison::new(
+        &RgbColour {
+          red: 0,
+          green: 0,
+          blue: 0,
+        },
+        &RgbColour {
+          red: 255,
+          green: 255,
+          blue: 255,
+        },
       )
+      .oklab();
+      assert!((result.0 - 1.0).abs() < 0.001);
+      assert_eq!(result.1, 0);
     }
   }
 }
@@ -0,0 +1,147 @@
+use crate::colour::RgbColour;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// APCA-W3 0.0.98G-4g constants.
+const APCA_TRC: f32 = 2.4;
+const APCA_COEFFICIENTS: (f32, f32, f32) = (0.212_672_9, 0.715_152_2, 0.072_175);
+const APCA_NORMAL_BACKGROUND: f32 = 0.56;
+const APCA_NORMAL_TEXT: f32 = 0.57;
+const APCA_REVERSE_TEXT: f32 = 0.62;
+const APCA_REVERSE_BACKGROUND: f32 = 0.65;
+const APCA_BLACK_THRESHOLD: f32 = 0.022;
+const APCA_BLACK_CLAMP: f32 = 1.414;
+const APCA_SCALE: f32 = 1.14;
+const APCA_OFFSET: f32 = 0.027;
+const APCA_DELTA_Y_MIN: f32 = 0.0005;
+const APCA_LOW_CLIP: f32 = 0.1;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum WcagLevel {
+  Fail,
+  AaLarge,
+  Aa,
+  Aaa,
+}
+
+// WCAG 2.x contrast ratio, from 1 to 21.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn contrast_ratio(a: &RgbColour, b: &RgbColour) -> f32 {
+  let (a, b) = (a.relative_luminance(), b.relative_luminance());
+  (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn wcag_level(a: &RgbColour, b: &RgbColour) -> WcagLevel {
+  match contrast_ratio(a, b) {
+    ratio if ratio >= 7.0 => WcagLevel::Aaa,
+    ratio if ratio >= 4.5 => WcagLevel::Aa,
+    ratio if ratio >= 3.0 => WcagLevel::AaLarge,
+    _ => WcagLevel::Fail,
+  }
+}
+
+// APCA lightness contrast (Lc), positive for dark text on light backgrounds and negative for the reverse.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn apca_contrast(text: &RgbColour, background: &RgbColour) -> f32 {
+  let luminance = |colour: &RgbColour| {
+    let channel = |value: u8| (value as f32 / 255.0).powf(APCA_TRC);
+    let y = APCA_COEFFICIENTS.0 * channel(colour.red)
+      + APCA_COEFFICIENTS.1 * channel(colour.green)
+      + APCA_COEFFICIENTS.2 * channel(colour.blue);
+    if y > APCA_BLACK_THRESHOLD {
+      y
+    } else {
+      y + (APCA_BLACK_THRESHOLD - y).powf(APCA_BLACK_CLAMP)
+    }
+  };
+
+  let text = luminance(text);
+  let background = luminance(background);
+  if (background - text).abs() < APCA_DELTA_Y_MIN {
+    return 0.0;
+  }
+
+  let contrast = if background > text {
+    let sapc = (background.powf(APCA_NORMAL_BACKGROUND) - text.powf(APCA_NORMAL_TEXT)) * APCA_SCALE;
+    if sapc < APCA_LOW_CLIP {
+      0.0
+    } else {
+      sapc - APCA_OFFSET
+    }
+  } else {
+    let sapc =
+      (background.powf(APCA_REVERSE_BACKGROUND) - text.powf(APCA_REVERSE_TEXT)) * APCA_SCALE;
+    if sapc > -APCA_LOW_CLIP {
+      0.0
+    } else {
+      sapc + APCA_OFFSET
+    }
+  };
+
+  contrast * 100.0
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl RgbColour {
+  // WCAG relative luminance of the sRGB colour.
+  pub fn relative_luminance(&self) -> f32 {
+    let (red, green, blue) = self.to_linear();
+    0.2126 * red + 0.7152 * green + 0.0722 * blue
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn black() -> RgbColour {
+    RgbColour::from_tuple(0, 0, 0)
+  }
+
+  fn white() -> RgbColour {
+    RgbColour::from_tuple(255, 255, 255)
+  }
+
+  mod contrast_ratio {
+    use super::*;
+
+    #[test]
+    fn black_and_white() {
+      assert!((contrast_ratio(&black(), &white()) - 21.0).abs() < 0.001);
+      assert!((contrast_ratio(&white(), &black()) - 21.0).abs() < 0.001);
+      assert_eq!(contrast_ratio(&white(), &white()), 1.0);
+    }
+
+    #[test]
+    fn levels() {
+      let grey = RgbColour::from_tuple(118, 118, 118);
+      assert_eq!(wcag_level(&grey, &white()), WcagLevel::Aa);
+      assert_eq!(wcag_level(&black(), &white()), WcagLevel::Aaa);
+      assert_eq!(
+        wcag_level(&RgbColour::from_tuple(150, 150, 150), &white()),
+        WcagLevel::Fail
+      );
+    }
+  }
+
+  mod apca_contrast {
+    use super::*;
+
+    #[test]
+    fn reference_values() {
+      assert!((apca_contrast(&black(), &white()) - 106.04).abs() < 0.01);
+      assert!((apca_contrast(&white(), &black()) + 107.88).abs() < 0.01);
+      let text = RgbColour::from_tuple(0x88, 0x88, 0x88);
+      assert!((apca_contrast(&text, &white()) - 63.06).abs() < 0.05);
+    }
+
+    #[test]
+    fn same_colour() {
+      assert_eq!(apca_contrast(&white(), &white()), 0.0);
+    }
+  }
+}
@@ -0,0 +1,175 @@
+use crate::colour::RgbColour;
+use crate::comparison::Metric;
+use crate::error_code::ErrorCode;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct DifferenceMap {
+  deltas: Vec<f32>,
+  sorted: Vec<f32>,
+}
+
+// Blends a pixel over white so that differences in transparency are visible.
+fn composite(pixel: &[u8]) -> RgbColour {
+  let alpha = pixel[3] as f32 / 255.0;
+  let blend = |channel: u8| (channel as f32 * alpha + 255.0 * (1.0 - alpha)).round() as u8;
+  RgbColour::from_tuple(blend(pixel[0]), blend(pixel[1]), blend(pixel[2]))
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl DifferenceMap {
+  pub fn deltas(&self) -> Vec<f32> {
+    self.deltas.clone()
+  }
+
+  pub fn mean(&self) -> f32 {
+    if self.deltas.is_empty() {
+      return 0.0;
+    }
+
+    self.deltas.iter().sum::<f32>() / self.deltas.len() as f32
+  }
+
+  pub fn max(&self) -> f32 {
+    self.sorted.last().cloned().unwrap_or(0.0)
+  }
+
+  pub fn percentile(&self, percentile: f32) -> f32 {
+    if self.sorted.is_empty() {
+      return 0.0;
+    }
+
+    let rank = (percentile.clamp(0.0, 100.0) / 100.0 * self.sorted.len() as f32).ceil() as usize;
+    self.sorted[rank.max(1) - 1]
+  }
+
+  pub fn count_above(&self, threshold: f32) -> u32 {
+    (self.sorted.len() - self.sorted.partition_point(|delta| *delta <= threshold)) as u32
+  }
+
+  // Renders the deltas as RGBA, going from black through red and yellow to white at `scale`.
+  pub fn heatmap(&self, scale: f32) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(self.deltas.len() * 4);
+    for delta in self.deltas.iter() {
+      let amount = if scale > 0.0 {
+        (delta / scale).clamp(0.0, 1.0) * 3.0
+      } else {
+        0.0
+      };
+      let channel = |offset: f32| ((amount - offset).clamp(0.0, 1.0) * 255.0).round() as u8;
+      pixels.extend_from_slice(&[channel(0.0), channel(1.0), channel(2.0), 255]);
+    }
+    pixels
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn compare_images(a: &[u8], b: &[u8], metric: Metric) -> Result<DifferenceMap, ErrorCode> {
+  if a.len() != b.len() {
+    return Err(ErrorCode::BufferSizeMismatch {
+      expected: a.len(),
+      found: b.len(),
+    });
+  }
+
+  if !a.len().is_multiple_of(4) {
+    return Err(ErrorCode::InvalidBufferLength(a.len()));
+  }
+
+  let deltas: Vec<f32> = a
+    .chunks_exact(4)
+    .zip(b.chunks_exact(4))
+    .map(|(x, y)| {
+      if x == y {
+        return 0.0;
+      }
+
+      metric.distance(
+        &metric.coordinates(composite(x)),
+        &metric.coordinates(composite(y)),
+      )
+    })
+    .collect();
+  let mut sorted = deltas.clone();
+  sorted.sort_by(|x, y| x.partial_cmp(y).unwrap());
+
+  Ok(DifferenceMap { deltas, sorted })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn difference() -> DifferenceMap {
+    let a = vec![
+      0, 0, 0, 255, 255, 255, 255, 255, 244, 43, 32, 255, 10, 20, 30, 255,
+    ];
+    let b = vec![
+      0, 0, 0, 255, 0, 0, 0, 255, 244, 43, 32, 255, 12, 20, 30, 255,
+    ];
+    compare_images(&a, &b, Metric::Ciede2000).unwrap()
+  }
+
+  mod compare_images {
+    use super::*;
+
+    #[test]
+    fn deltas() {
+      let deltas = difference().deltas();
+      assert_eq!(deltas[0], 0.0);
+      assert!((deltas[1] - 100.0).abs() < 0.001);
+      assert_eq!(deltas[2], 0.0);
+      assert!(deltas[3] > 0.0 && deltas[3] < 2.0);
+    }
+
+    #[test]
+    fn transparency() {
+      let result = compare_images(&[0, 0, 0, 255], &[0, 0, 0, 0], Metric::Rgb).unwrap();
+      assert!((result.max() - 441.67294).abs() < 0.001);
+    }
+  }
+
+  mod summary {
+    use super::*;
+
+    #[test]
+    fn mean_and_max() {
+      let result = difference();
+      assert!((result.max() - 100.0).abs() < 0.001);
+      assert!((result.mean() - (100.0 + result.deltas()[3]) / 4.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn percentile() {
+      let result = difference();
+      assert_eq!(result.percentile(50.0), 0.0);
+      assert_eq!(result.percentile(75.0), result.deltas()[3]);
+      assert_eq!(result.percentile(100.0), result.max());
+    }
+
+    #[test]
+    fn count_above() {
+      let result = difference();
+      assert_eq!(result.count_above(0.0), 2);
+      assert_eq!(result.count_above(2.3), 1);
+      assert_eq!(result.count_above(100.0), 0);
+    }
+  }
+
+  mod heatmap {
+    use super::*;
+
+    #[test]
+    fn ramp() {
+      let heatmap = difference().heatmap(100.0);
+      assert_eq!(heatmap[0..4], [0, 0, 0, 255]);
+      assert_eq!(heatmap[4..8], [255, 255, 255, 255]);
+      assert_eq!(heatmap.len(), 16);
+    }
+  }
+}
@@ -0,0 +1,385 @@
+use crate::colour::RgbColour;
+use crate::comparison::Metric;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Dithering {
+  None,
+  FloydSteinberg,
+  Atkinson,
+  JarvisJudiceNinke,
+  Sierra,
+  Bayer2,
+  Bayer4,
+  Bayer8,
+}
+
+// (dx, dy, weight) offsets for each error diffusion kernel, along with the divisor.
+type Kernel = (&'static [(i32, i32, f32)], f32);
+
+const FLOYD_STEINBERG: Kernel = (&[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)], 16.0);
+
+const ATKINSON: Kernel = (
+  &[
+    (1, 0, 1.0),
+    (2, 0, 1.0),
+    (-1, 1, 1.0),
+    (0, 1, 1.0),
+    (1, 1, 1.0),
+    (0, 2, 1.0),
+  ],
+  8.0,
+);
+
+const JARVIS_JUDICE_NINKE: Kernel = (
+  &[
+    (1, 0, 7.0),
+    (2, 0, 5.0),
+    (-2, 1, 3.0),
+    (-1, 1, 5.0),
+    (0, 1, 7.0),
+    (1, 1, 5.0),
+    (2, 1, 3.0),
+    (-2, 2, 1.0),
+    (-1, 2, 3.0),
+    (0, 2, 5.0),
+    (1, 2, 3.0),
+    (2, 2, 1.0),
+  ],
+  48.0,
+);
+
+const SIERRA: Kernel = (
+  &[
+    (1, 0, 5.0),
+    (2, 0, 3.0),
+    (-2, 1, 2.0),
+    (-1, 1, 4.0),
+    (0, 1, 5.0),
+    (1, 1, 4.0),
+    (2, 1, 2.0),
+    (-1, 2, 2.0),
+    (0, 2, 3.0),
+    (1, 2, 2.0),
+  ],
+  32.0,
+);
+
+impl Dithering {
+  fn kernel(self) -> Option<Kernel> {
+    match self {
+      Dithering::FloydSteinberg => Some(FLOYD_STEINBERG),
+      Dithering::Atkinson => Some(ATKINSON),
+      Dithering::JarvisJudiceNinke => Some(JARVIS_JUDICE_NINKE),
+      Dithering::Sierra => Some(SIERRA),
+      _ => None,
+    }
+  }
+
+  fn bayer_size(self) -> Option<usize> {
+    match self {
+      Dithering::Bayer2 => Some(2),
+      Dithering::Bayer4 => Some(4),
+      Dithering::Bayer8 => Some(8),
+      _ => None,
+    }
+  }
+}
+
+// Builds an ordered dithering matrix of thresholds in the range [-0.5, 0.5).
+fn bayer_matrix(size: usize) -> Vec<f32> {
+  let mut matrix = vec![0_usize];
+  let mut current = 1;
+
+  while current < size {
+    let next = current * 2;
+    let mut expanded = vec![0; next * next];
+    for y in 0..current {
+      for x in 0..current {
+        let value = matrix[y * current + x] * 4;
+        expanded[y * next + x] = value;
+        expanded[y * next + x + current] = value + 2;
+        expanded[(y + current) * next + x] = value + 3;
+        expanded[(y + current) * next + x + current] = value + 1;
+      }
+    }
+    matrix = expanded;
+    current = next;
+  }
+
+  let cells = (size * size) as f32;
+  matrix
+    .into_iter()
+    .map(|value| (value as f32 + 0.5) / cells - 0.5)
+    .collect()
+}
+
+struct PaletteLookup {
+  colours: Vec<RgbColour>,
+  coordinates: Vec<(f32, f32, f32)>,
+  linear: Vec<(f32, f32, f32)>,
+  metric: Metric,
+}
+
+impl PaletteLookup {
+  fn new(colours: Vec<RgbColour>, metric: Metric) -> PaletteLookup {
+    PaletteLookup {
+      coordinates: colours
+        .iter()
+        .map(|colour| metric.coordinates(*colour))
+        .collect(),
+      linear: colours.iter().map(|colour| colour.to_linear()).collect(),
+      colours,
+      metric,
+    }
+  }
+
+  fn nearest(&self, colour: RgbColour) -> usize {
+    let target = self.metric.coordinates(colour);
+    let mut nearest = 0;
+    let mut nearest_distance = f32::INFINITY;
+    for (index, coordinates) in self.coordinates.iter().enumerate() {
+      let distance = self.metric.distance(&target, coordinates);
+      if distance < nearest_distance {
+        nearest = index;
+        nearest_distance = distance;
+      }
+    }
+    nearest
+  }
+
+  // How far apart neighbouring palette levels are per channel, used to scale ordered dithering.
+  fn spread(&self) -> f32 {
+    let levels = (self.colours.len() as f32).cbrt().round().max(2.0);
+    1.0 / (levels - 1.0)
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn nearest_colour(
+  colour: &RgbColour,
+  palette: Vec<RgbColour>,
+  metric: Metric,
+) -> Option<RgbColour> {
+  if palette.is_empty() {
+    return None;
+  }
+
+  let palette = PaletteLookup::new(palette, metric);
+  Some(palette.colours[palette.nearest(*colour)])
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn remap_to_palette(
+  pixels: &mut [u8],
+  width: u32,
+  palette: Vec<RgbColour>,
+  metric: Metric,
+  dithering: Dithering,
+) {
+  if palette.is_empty() || width == 0 {
+    return;
+  }
+
+  let palette = PaletteLookup::new(palette, metric);
+  let width = width as usize;
+  let height = pixels.len() / 4 / width;
+  let mut linear: Vec<(f32, f32, f32)> = pixels
+    .chunks_exact(4)
+    .map(|pixel| RgbColour::from_tuple(pixel[0], pixel[1], pixel[2]).to_linear())
+    .collect();
+  let bayer = dithering
+    .bayer_size()
+    .map(|size| (size, bayer_matrix(size)));
+  let spread = palette.spread();
+
+  for y in 0..height {
+    for x in 0..width {
+      let index = y * width + x;
+      let mut wanted = linear[index];
+
+      if let Some((size, matrix)) = &bayer {
+        let offset = matrix[(y % size) * size + x % size] * spread;
+        wanted = (wanted.0 + offset, wanted.1 + offset, wanted.2 + offset);
+      }
+
+      let chosen = palette.nearest(RgbColour::from_linear(wanted.0, wanted.1, wanted.2));
+      let colour = palette.colours[chosen];
+      pixels[index * 4] = colour.red;
+      pixels[index * 4 + 1] = colour.green;
+      pixels[index * 4 + 2] = colour.blue;
+
+      if let Some((kernel, divisor)) = dithering.kernel() {
+        let actual = palette.linear[chosen];
+        let error = (
+          wanted.0.clamp(0.0, 1.0) - actual.0,
+          wanted.1.clamp(0.0, 1.0) - actual.1,
+          wanted.2.clamp(0.0, 1.0) - actual.2,
+        );
+
+        for &(dx, dy, weight) in kernel {
+          let target_x = x as i32 + dx;
+          let target_y = y + dy as usize;
+          if target_x < 0 || target_x >= width as i32 || target_y >= height {
+            continue;
+          }
+
+          let factor = weight / divisor;
+          let target = &mut linear[target_y * width + target_x as usize];
+          target.0 += error.0 * factor;
+          target.1 += error.1 * factor;
+          target.2 += error.2 * factor;
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const BLACK: RgbColour = RgbColour {
+    red: 0,
+    green: 0,
+    blue: 0,
+  };
+  const WHITE: RgbColour = RgbColour {
+    red: 255,
+    green: 255,
+    blue: 255,
+  };
+
+  fn solid(colour: RgbColour, pixel_count: usize) -> Vec<u8> {
+    (0..pixel_count)
+      .flat_map(|_| vec![colour.red, colour.green, colour.blue, 255])
+      .collect()
+  }
+
+  fn count(pixels: &[u8], colour: RgbColour) -> usize {
+    pixels
+      .chunks_exact(4)
+      .filter(|pixel| pixel[0..3] == [colour.red, colour.green, colour.blue])
+      .count()
+  }
+
+  mod bayer_matrix {
+    use super::*;
+
+    #[test]
+    fn two_by_two() {
+      assert_eq!(bayer_matrix(2), vec![-0.375, 0.125, 0.375, -0.125])
+    }
+
+    #[test]
+    fn eight_by_eight_is_balanced() {
+      let matrix = bayer_matrix(8);
+      assert_eq!(matrix.len(), 64);
+      assert!(matrix.iter().sum::<f32>().abs() < 0.0001);
+    }
+  }
+
+  mod nearest_colour {
+    use super::*;
+
+    #[test]
+    fn lab() {
+      let palette = vec![
+        BLACK,
+        WHITE,
+        RgbColour {
+          red: 200,
+          green: 30,
+          blue: 40,
+        },
+      ];
+      assert_eq!(
+        nearest_colour(
+          &RgbColour {
+            red: 244,
+            green: 43,
+            blue: 32
+          },
+          palette,
+          Metric::Lab
+        ),
+        Some(RgbColour {
+          red: 200,
+          green: 30,
+          blue: 40
+        })
+      )
+    }
+
+    #[test]
+    fn empty_palette() {
+      assert_eq!(nearest_colour(&WHITE, vec![], Metric::Rgb), None)
+    }
+  }
+
+  mod remap_to_palette {
+    use super::*;
+
+    #[test]
+    fn without_dithering() {
+      let mut pixels = vec![10, 20, 30, 255, 240, 230, 220, 128];
+      remap_to_palette(
+        &mut pixels,
+        2,
+        vec![BLACK, WHITE],
+        Metric::Lab,
+        Dithering::None,
+      );
+      assert_eq!(pixels, vec![0, 0, 0, 255, 255, 255, 255, 128])
+    }
+
+    #[test]
+    fn error_diffusion_preserves_average_light() {
+      // 188 is half intensity in linear light, so roughly half the pixels should be white.
+      let grey = RgbColour::from_tuple(188, 188, 188);
+      for &dithering in &[
+        Dithering::FloydSteinberg,
+        Dithering::JarvisJudiceNinke,
+        Dithering::Sierra,
+      ] {
+        let mut pixels = solid(grey, 256);
+        remap_to_palette(&mut pixels, 16, vec![BLACK, WHITE], Metric::Lab, dithering);
+        let whites = count(&pixels, WHITE);
+        assert_eq!(whites + count(&pixels, BLACK), 256);
+        assert!(whites > 112 && whites < 144, "{:?}: {}", dithering, whites);
+      }
+    }
+
+    #[test]
+    fn atkinson_loses_some_error() {
+      let grey = RgbColour::from_tuple(188, 188, 188);
+      let mut pixels = solid(grey, 256);
+      remap_to_palette(
+        &mut pixels,
+        16,
+        vec![BLACK, WHITE],
+        Metric::Rgb,
+        Dithering::Atkinson,
+      );
+      let whites = count(&pixels, WHITE);
+      assert!(whites > 0 && whites < 256);
+    }
+
+    #[test]
+    fn ordered() {
+      let grey = RgbColour::from_tuple(188, 188, 188);
+      for &dithering in &[Dithering::Bayer2, Dithering::Bayer4, Dithering::Bayer8] {
+        let mut pixels = solid(grey, 64);
+        remap_to_palette(&mut pixels, 8, vec![BLACK, WHITE], Metric::Rgb, dithering);
+        let whites = count(&pixels, WHITE);
+        assert_eq!(whites + count(&pixels, BLACK), 64);
+        assert!(whites > 0 && whites < 64, "{:?}: {}", dithering, whites);
+      }
+    }
+  }
+}
@@ -1,29 +1,186 @@
-#[derive(Debug)]
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum ErrorCode {
-  InvalidHexCharacter(String),
-  InvalidHexLength(usize),
+  InvalidHexCharacter {
+    value: String,
+    position: usize,
+    found: char,
+  },
+  InvalidHexLength {
+    value: String,
+    found: usize,
+  },
   InvalidColourName(String),
+  InvalidCssFunction {
+    value: String,
+    position: usize,
+    expected: String,
+  },
+  InvalidColour {
+    value: String,
+    source: Box<ErrorCode>,
+  },
   CanvasError,
-  HslConversionError(String),
+  InvalidBufferLength(usize),
+  BufferSizeMismatch {
+    expected: usize,
+    found: usize,
+  },
+  InvalidPalette {
+    format: String,
+    position: usize,
+    expected: String,
+  },
 }
 
-impl std::convert::From<ErrorCode> for String {
-  fn from(error: ErrorCode) -> String {
-    match error {
-      ErrorCode::InvalidHexCharacter(value) => {
-        format!("Invalid: found invalid characters in hex code: {}", value)
-      }
-      ErrorCode::InvalidHexLength(length) => format!(
-        "Invalid: hex code has invalid length: {}. Length must be 3 or 6.",
-        length
+impl ErrorCode {
+  pub fn code(&self) -> &'static str {
+    match self {
+      ErrorCode::InvalidHexCharacter { .. } => "INVALID_HEX_CHARACTER",
+      ErrorCode::InvalidHexLength { .. } => "INVALID_HEX_LENGTH",
+      ErrorCode::InvalidColourName(_) => "INVALID_COLOUR_NAME",
+      ErrorCode::InvalidCssFunction { .. } => "INVALID_CSS_FUNCTION",
+      ErrorCode::InvalidColour { .. } => "INVALID_COLOUR",
+      ErrorCode::CanvasError => "CANVAS_ERROR",
+      ErrorCode::InvalidBufferLength(_) => "INVALID_BUFFER_LENGTH",
+      ErrorCode::BufferSizeMismatch { .. } => "BUFFER_SIZE_MISMATCH",
+      ErrorCode::InvalidPalette { .. } => "INVALID_PALETTE",
+    }
+  }
+}
+
+impl core::fmt::Display for ErrorCode {
+  fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    match self {
+      ErrorCode::InvalidHexCharacter {
+        value,
+        position,
+        found,
+      } => write!(
+        f,
+        "Invalid: found invalid character {:?} at position {} in hex code: {}",
+        found, position, value
+      ),
+      ErrorCode::InvalidHexLength { value, found } => write!(
+        f,
+        "Invalid: hex code {} has invalid length: {}. Length must be 3 or 6.",
+        value, found
       ),
       ErrorCode::InvalidColourName(value) => {
-        format!("Invalid: {} is not a valid css colour name", value)
+        write!(f, "Invalid: {} is not a valid css colour name", value)
       }
-      ErrorCode::CanvasError => {
-        "Canvas: error occurred while getting image data from canvas".to_string()
+      ErrorCode::InvalidCssFunction {
+        value,
+        position,
+        expected,
+      } => write!(
+        f,
+        "Invalid: expected {} at position {} in css colour: {}",
+        expected, position, value
+      ),
+      ErrorCode::InvalidColour { value, .. } => {
+        write!(f, "Invalid: could not parse {} as a colour", value)
       }
-      ErrorCode::HslConversionError(rgb) => format!("HSL: could not convert {} to HSL format", rgb),
+      ErrorCode::CanvasError => write!(
+        f,
+        "Canvas: error occurred while getting image data from canvas"
+      ),
+      ErrorCode::InvalidBufferLength(length) => write!(
+        f,
+        "Invalid: pixel buffer has invalid length: {}. Length must be a multiple of 4.",
+        length
+      ),
+      ErrorCode::BufferSizeMismatch { expected, found } => write!(
+        f,
+        "Invalid: pixel buffers have different lengths: expected {}, found {}",
+        expected, found
+      ),
+      ErrorCode::InvalidPalette {
+        format,
+        position,
+        expected,
+      } => write!(
+        f,
+        "Invalid: expected {} at byte {} in {} palette",
+        expected, position, format
+      ),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ErrorCode {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      ErrorCode::InvalidColour { source, .. } => Some(source.as_ref()),
+      _ => None,
+    }
+  }
+}
+
+impl core::convert::From<ErrorCode> for String {
+  fn from(error: ErrorCode) -> String {
+    error.to_string()
+  }
+}
+
+// Surfaces as a JS `Error` with a `code` property, and the source error as its `cause`.
+#[cfg(feature = "wasm")]
+impl core::convert::From<ErrorCode> for JsValue {
+  fn from(error: ErrorCode) -> JsValue {
+    let js_error = js_sys::Error::new(&error.to_string());
+    let _ = js_sys::Reflect::set(
+      &js_error,
+      &JsValue::from_str("code"),
+      &JsValue::from_str(error.code()),
+    );
+    if let ErrorCode::InvalidColour { source, .. } = error {
+      js_error.set_cause(&JsValue::from(*source));
+    }
+    js_error.into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::error::Error;
+
+  mod display {
+    use super::*;
+
+    #[test]
+    fn hex_character() {
+      let error = ErrorCode::InvalidHexCharacter {
+        value: "F43C8X".to_string(),
+        position: 5,
+        found: 'X',
+      };
+      assert_eq!(
+        error.to_string(),
+        "Invalid: found invalid character 'X' at position 5 in hex code: F43C8X"
+      );
+      assert_eq!(error.code(), "INVALID_HEX_CHARACTER");
+    }
+  }
+
+  mod source {
+    use super::*;
+
+    #[test]
+    fn chained() {
+      let error = ErrorCode::InvalidColour {
+        value: "rust".to_string(),
+        source: Box::new(ErrorCode::InvalidColourName("rust".to_string())),
+      };
+      assert_eq!(
+        error.source().map(|source| source.to_string()),
+        Some("Invalid: rust is not a valid css colour name".to_string())
+      );
+      assert!(ErrorCode::CanvasError.source().is_none());
     }
   }
 }
@@ -0,0 +1,327 @@
+use crate::colour::RgbColour;
+use crate::comparison::Comparison;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Dominant colours closer than this (in Lab delta E) are treated as the same colour.
+const MIN_DOMINANT_DISTANCE: f32 = 10.0;
+const BIN_BITS: u32 = 5;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ExtractionOptions {
+  pub stride: usize,
+  pub alpha_threshold: u8,
+  pub saturation_weight: f32,
+  pub centre_weight: f32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl ExtractionOptions {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new() -> ExtractionOptions {
+    ExtractionOptions {
+      stride: 1,
+      alpha_threshold: 1,
+      saturation_weight: 0.0,
+      centre_weight: 0.0,
+    }
+  }
+}
+
+impl Default for ExtractionOptions {
+  fn default() -> Self {
+    ExtractionOptions::new()
+  }
+}
+
+#[derive(Debug, Default, Copy, Clone)]
+struct Accumulator {
+  weight: f32,
+  red: f32,
+  green: f32,
+  blue: f32,
+}
+
+impl Accumulator {
+  fn add(&mut self, colour: (f32, f32, f32), weight: f32) {
+    self.weight += weight;
+    self.red += colour.0 * weight;
+    self.green += colour.1 * weight;
+    self.blue += colour.2 * weight;
+  }
+
+  fn mean(&self) -> Option<RgbColour> {
+    if self.weight <= 0.0 {
+      return None;
+    }
+
+    Some(RgbColour::from_linear(
+      self.red / self.weight,
+      self.green / self.weight,
+      self.blue / self.weight,
+    ))
+  }
+}
+
+// Calls `visit` with the colour, linear-light channels and weight of every sampled pixel.
+fn sample_pixels<F>(pixels: &[u8], width: u32, options: &ExtractionOptions, mut visit: F)
+where
+  F: FnMut(RgbColour, (f32, f32, f32), f32),
+{
+  let pixel_count = pixels.len() / 4;
+  let width = (width as usize).max(1);
+  let height = pixel_count.div_ceil(width).max(1);
+  let centre = ((width as f32 - 1.0) / 2.0, (height as f32 - 1.0) / 2.0);
+  let max_distance = (centre.0 * centre.0 + centre.1 * centre.1).sqrt().max(1.0);
+
+  for index in (0..pixel_count).step_by(options.stride.max(1)) {
+    let pixel = &pixels[index * 4..index * 4 + 4];
+    if pixel[3] < options.alpha_threshold || pixel[3] == 0 {
+      continue;
+    }
+
+    let colour = RgbColour::from_tuple(pixel[0], pixel[1], pixel[2]);
+    let mut weight = pixel[3] as f32 / 255.0;
+
+    if options.saturation_weight != 0.0 {
+      let max = colour.red.max(colour.green).max(colour.blue) as f32;
+      let min = colour.red.min(colour.green).min(colour.blue) as f32;
+      let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+      weight *= 1.0 + options.saturation_weight * saturation;
+    }
+
+    if options.centre_weight != 0.0 {
+      let x = (index % width) as f32 - centre.0;
+      let y = (index / width) as f32 - centre.1;
+      let closeness = 1.0 - (x * x + y * y).sqrt() / max_distance;
+      weight *= 1.0 + options.centre_weight * closeness;
+    }
+
+    if weight > 0.0 {
+      visit(colour, colour.to_linear(), weight);
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn average_colour(pixels: &[u8], width: u32, options: &ExtractionOptions) -> Option<RgbColour> {
+  let mut total = Accumulator::default();
+  sample_pixels(pixels, width, options, |_, linear, weight| {
+    total.add(linear, weight)
+  });
+  total.mean()
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn dominant_colours(
+  pixels: &[u8],
+  width: u32,
+  count: usize,
+  options: &ExtractionOptions,
+) -> Vec<RgbColour> {
+  let shift = 8 - BIN_BITS;
+  let mut bins = vec![Accumulator::default(); 1 << (BIN_BITS * 3)];
+  sample_pixels(pixels, width, options, |colour, linear, weight| {
+    let index = ((colour.red as usize >> shift) << (BIN_BITS * 2))
+      | ((colour.green as usize >> shift) << BIN_BITS)
+      | (colour.blue as usize >> shift);
+    bins[index].add(linear, weight);
+  });
+
+  bins.retain(|bin| bin.weight > 0.0);
+  bins.sort_by(|a, b| b.weight.partial_cmp(&a.weight).unwrap());
+
+  let mut dominant: Vec<RgbColour> = Vec::with_capacity(count);
+  for colour in bins.iter().filter_map(Accumulator::mean) {
+    if dominant.len() >= count {
+      break;
+    }
+
+    let is_distinct = dominant
+      .iter()
+      .all(|chosen| Comparison::new(chosen, &colour).lab().0 >= MIN_DOMINANT_DISTANCE);
+    if is_distinct {
+      dominant.push(colour);
+    }
+  }
+
+  dominant
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fixtures::image;
+
+  mod average_colour {
+    use super::*;
+
+    #[test]
+    fn single_colour() {
+      let pixels = image(&[(244, 43, 32, 255); 4]);
+      assert_eq!(
+        average_colour(&pixels, 2, &ExtractionOptions::new()),
+        Some(RgbColour {
+          red: 244,
+          green: 43,
+          blue: 32
+        })
+      )
+    }
+
+    #[test]
+    fn black_and_white_in_linear_light() {
+      let pixels = image(&[(0, 0, 0, 255), (255, 255, 255, 255)]);
+      assert_eq!(
+        average_colour(&pixels, 2, &ExtractionOptions::new()),
+        Some(RgbColour {
+          red: 188,
+          green: 188,
+          blue: 188
+        })
+      )
+    }
+
+    #[test]
+    fn transparent_pixels_are_masked() {
+      let pixels = image(&[(255, 0, 0, 255), (0, 0, 255, 0)]);
+      assert_eq!(
+        average_colour(&pixels, 2, &ExtractionOptions::new()),
+        Some(RgbColour {
+          red: 255,
+          green: 0,
+          blue: 0
+        })
+      )
+    }
+
+    #[test]
+    fn fully_masked() {
+      let pixels = image(&[(255, 0, 0, 10), (0, 0, 255, 20)]);
+      let options = ExtractionOptions {
+        alpha_threshold: 128,
+        ..ExtractionOptions::new()
+      };
+      assert_eq!(average_colour(&pixels, 2, &options), None)
+    }
+
+    #[test]
+    fn stride() {
+      let pixels = image(&[(255, 0, 0, 255), (0, 0, 255, 255), (255, 0, 0, 255)]);
+      let options = ExtractionOptions {
+        stride: 2,
+        ..ExtractionOptions::new()
+      };
+      assert_eq!(
+        average_colour(&pixels, 3, &options),
+        Some(RgbColour {
+          red: 255,
+          green: 0,
+          blue: 0
+        })
+      )
+    }
+  }
+
+  mod dominant_colours {
+    use super::*;
+
+    #[test]
+    fn most_common_first() {
+      let pixels = image(&[
+        (20, 20, 200, 255),
+        (240, 30, 30, 255),
+        (240, 30, 30, 255),
+        (20, 20, 200, 255),
+        (240, 30, 30, 255),
+        (240, 30, 30, 255),
+      ]);
+      assert_eq!(
+        dominant_colours(&pixels, 3, 2, &ExtractionOptions::new()),
+        vec![
+          RgbColour {
+            red: 240,
+            green: 30,
+            blue: 30
+          },
+          RgbColour {
+            red: 20,
+            green: 20,
+            blue: 200
+          }
+        ]
+      )
+    }
+
+    #[test]
+    fn similar_colours_are_not_repeated() {
+      let pixels = image(&[
+        (240, 30, 30, 255),
+        (240, 30, 30, 255),
+        (236, 34, 30, 255),
+        (20, 20, 200, 255),
+      ]);
+      assert_eq!(
+        dominant_colours(&pixels, 4, 2, &ExtractionOptions::new()),
+        vec![
+          RgbColour {
+            red: 240,
+            green: 30,
+            blue: 30
+          },
+          RgbColour {
+            red: 20,
+            green: 20,
+            blue: 200
+          }
+        ]
+      )
+    }
+
+    #[test]
+    fn saturation_weight() {
+      let pixels = image(&[
+        (128, 128, 128, 255),
+        (128, 128, 128, 255),
+        (20, 200, 20, 255),
+      ]);
+      let options = ExtractionOptions {
+        saturation_weight: 2.0,
+        ..ExtractionOptions::new()
+      };
+      assert_eq!(
+        dominant_colours(&pixels, 3, 1, &options),
+        vec![RgbColour {
+          red: 20,
+          green: 200,
+          blue: 20
+        }]
+      )
+    }
+
+    #[test]
+    fn centre_weight() {
+      let mut colours = vec![(0, 0, 0, 0); 9];
+      for &corner in &[0, 2, 6, 8] {
+        colours[corner] = (128, 128, 128, 255);
+      }
+      colours[4] = (20, 200, 20, 255);
+      let options = ExtractionOptions {
+        centre_weight: 10.0,
+        ..ExtractionOptions::new()
+      };
+      assert_eq!(
+        dominant_colours(&image(&colours), 3, 1, &options),
+        vec![RgbColour {
+          red: 20,
+          green: 200,
+          blue: 20
+        }]
+      )
+    }
+  }
+}
@@ -0,0 +1,478 @@
+use crate::batch::compare_to_reference;
+use crate::batch::rgba_to_space;
+use crate::batch::space_to_rgba;
+use crate::batch::BatchSpace;
+use crate::colour::RgbColour;
+use crate::comparison::Metric;
+use crate::dithering::remap_to_palette;
+use crate::dithering::Dithering;
+use crate::error_code::ErrorCode;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::prelude::*;
+use crate::simulation::Deficiency;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+#[cfg(feature = "wasm")]
+use web_sys::{CanvasRenderingContext2d, ImageData};
+
+#[derive(Debug, PartialEq, Clone)]
+enum Operation {
+  Grayscale,
+  Simulate {
+    deficiency: Deficiency,
+    severity: f32,
+  },
+  Remap {
+    palette: Vec<RgbColour>,
+    metric: Metric,
+    dithering: Dithering,
+  },
+  HueRotate(f32),
+  BrightnessContrast {
+    brightness: f32,
+    contrast: f32,
+  },
+  ReplaceColour {
+    target: RgbColour,
+    replacement: RgbColour,
+    tolerance: f32,
+    softness: f32,
+  },
+  ChromaKey {
+    target: RgbColour,
+    tolerance: f32,
+    softness: f32,
+  },
+}
+
+// An in-place operation over RGBA pixels. Only chroma keying touches alpha.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Filter(Operation);
+
+fn map_colours(pixels: &mut [u8], transform: impl Fn(RgbColour) -> RgbColour) {
+  for pixel in pixels.chunks_exact_mut(4) {
+    let colour = transform(RgbColour::from_tuple(pixel[0], pixel[1], pixel[2]));
+    pixel[..3].copy_from_slice(&[colour.red, colour.green, colour.blue]);
+  }
+}
+
+fn check_rows(pixels: &[u8], width: u32) -> Result<(), ErrorCode> {
+  let row = width as usize * 4;
+  let expected = match row {
+    0 => 0,
+    _ => pixels.len().div_ceil(row) * row,
+  };
+  if expected != pixels.len() {
+    return Err(ErrorCode::BufferSizeMismatch {
+      expected,
+      found: pixels.len(),
+    });
+  }
+  Ok(())
+}
+
+// Rotates around the OKLab lightness axis, so lightness and chroma are kept until clipping.
+fn hue_rotate(pixels: &mut [u8], degrees: f32) -> Result<(), ErrorCode> {
+  let (sin, cos) = (degrees.to_radians().sin(), degrees.to_radians().cos());
+  let mut oklab = vec![0.0; pixels.len() / 4 * 3];
+  rgba_to_space(pixels, &mut oklab, BatchSpace::Oklab)?;
+  for values in oklab.chunks_exact_mut(3) {
+    let (a, b) = (values[1], values[2]);
+    values[1] = a * cos - b * sin;
+    values[2] = a * sin + b * cos;
+  }
+  space_to_rgba(&oklab, pixels, BatchSpace::Oklab)
+}
+
+// CSS `brightness()` followed by `contrast()`, on the encoded sRGB values.
+fn brightness_contrast(pixels: &mut [u8], brightness: f32, contrast: f32) {
+  let mut levels = [0; 256];
+  for (level, value) in levels.iter_mut().enumerate() {
+    let adjusted = ((level as f32 / 255.0 * brightness - 0.5) * contrast + 0.5).clamp(0.0, 1.0);
+    *value = (adjusted * 255.0).round() as u8;
+  }
+  for pixel in pixels.chunks_exact_mut(4) {
+    for channel in &mut pixel[..3] {
+      *channel = levels[*channel as usize];
+    }
+  }
+}
+
+// How strongly each pixel matches `target`: 1 within `tolerance` (CIE76 delta E), easing down to 0
+// over the next `softness` units so keyed edges don't alias.
+fn key_weights(
+  pixels: &[u8],
+  target: &RgbColour,
+  tolerance: f32,
+  softness: f32,
+) -> Result<Vec<f32>, ErrorCode> {
+  let mut distances = vec![0.0; pixels.len() / 4];
+  compare_to_reference(pixels, target, Metric::Lab, &mut distances)?;
+  for distance in distances.iter_mut() {
+    *distance = if *distance <= tolerance {
+      1.0
+    } else if *distance >= tolerance + softness {
+      0.0
+    } else {
+      let t = 1.0 - (*distance - tolerance) / softness;
+      t * t * (3.0 - 2.0 * t)
+    };
+  }
+  Ok(distances)
+}
+
+// Mixes towards the replacement in linear light by each pixel's key weight.
+fn replace_colour(
+  pixels: &mut [u8],
+  target: &RgbColour,
+  replacement: &RgbColour,
+  tolerance: f32,
+  softness: f32,
+) -> Result<(), ErrorCode> {
+  let weights = key_weights(pixels, target, tolerance, softness)?;
+  let wanted = replacement.to_linear();
+  for (pixel, weight) in pixels.chunks_exact_mut(4).zip(weights) {
+    if weight == 0.0 {
+      continue;
+    }
+    let linear = RgbColour::from_tuple(pixel[0], pixel[1], pixel[2]).to_linear();
+    let mix = |from: f32, to: f32| from + (to - from) * weight;
+    let colour = RgbColour::from_linear(
+      mix(linear.0, wanted.0),
+      mix(linear.1, wanted.1),
+      mix(linear.2, wanted.2),
+    );
+    pixel[..3].copy_from_slice(&[colour.red, colour.green, colour.blue]);
+  }
+  Ok(())
+}
+
+fn chroma_key(
+  pixels: &mut [u8],
+  target: &RgbColour,
+  tolerance: f32,
+  softness: f32,
+) -> Result<(), ErrorCode> {
+  let weights = key_weights(pixels, target, tolerance, softness)?;
+  for (pixel, weight) in pixels.chunks_exact_mut(4).zip(weights) {
+    pixel[3] = (pixel[3] as f32 * (1.0 - weight)).round() as u8;
+  }
+  Ok(())
+}
+
+// One byte per pixel: 0 where it matches `target`, 255 beyond the soft edge, ready to use as alpha.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn chroma_key_mask(
+  pixels: &[u8],
+  target: &RgbColour,
+  tolerance: f32,
+  softness: f32,
+) -> Result<Vec<u8>, ErrorCode> {
+  Ok(
+    key_weights(pixels, target, tolerance, softness)?
+      .iter()
+      .map(|weight| ((1.0 - weight) * 255.0).round() as u8)
+      .collect(),
+  )
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Filter {
+  // Luminance-preserving greyscale, mixed in linear light.
+  pub fn grayscale() -> Filter {
+    Filter(Operation::Grayscale)
+  }
+
+  pub fn simulate(deficiency: Deficiency, severity: f32) -> Filter {
+    Filter(Operation::Simulate {
+      deficiency,
+      severity,
+    })
+  }
+
+  pub fn remap(palette: Vec<RgbColour>, metric: Metric, dithering: Dithering) -> Filter {
+    Filter(Operation::Remap {
+      palette,
+      metric,
+      dithering,
+    })
+  }
+
+  pub fn hue_rotate(degrees: f32) -> Filter {
+    Filter(Operation::HueRotate(degrees))
+  }
+
+  // 1 leaves the image unchanged for both, as with the CSS filters.
+  pub fn brightness_contrast(brightness: f32, contrast: f32) -> Filter {
+    Filter(Operation::BrightnessContrast {
+      brightness,
+      contrast,
+    })
+  }
+
+  // Replaces every pixel within `tolerance` of `target`, as CIE76 delta E, blending back to the
+  // original over a further `softness`. A softness of 0 gives a hard edge.
+  pub fn replace_colour(
+    target: &RgbColour,
+    replacement: &RgbColour,
+    tolerance: f32,
+    softness: f32,
+  ) -> Filter {
+    Filter(Operation::ReplaceColour {
+      target: *target,
+      replacement: *replacement,
+      tolerance,
+      softness,
+    })
+  }
+
+  // Makes matching pixels transparent instead, with the same tolerance and soft edge.
+  pub fn chroma_key(target: &RgbColour, tolerance: f32, softness: f32) -> Filter {
+    Filter(Operation::ChromaKey {
+      target: *target,
+      tolerance,
+      softness,
+    })
+  }
+
+  // `width` is the row length in pixels; palette remapping fails unless `pixels` holds whole rows.
+  pub fn apply(&self, pixels: &mut [u8], width: u32) -> Result<(), ErrorCode> {
+    if !pixels.len().is_multiple_of(4) {
+      return Err(ErrorCode::InvalidBufferLength(pixels.len()));
+    }
+
+    match &self.0 {
+      Operation::Grayscale => map_colours(pixels, |colour| {
+        let luminance = colour.relative_luminance();
+        RgbColour::from_linear(luminance, luminance, luminance)
+      }),
+      Operation::Simulate {
+        deficiency,
+        severity,
+      } => map_colours(pixels, |colour| colour.simulate(*deficiency, *severity)),
+      Operation::Remap {
+        palette,
+        metric,
+        dithering,
+      } => {
+        check_rows(pixels, width)?;
+        remap_to_palette(pixels, width, palette.clone(), *metric, *dithering)
+      }
+      Operation::HueRotate(degrees) => hue_rotate(pixels, *degrees)?,
+      Operation::BrightnessContrast {
+        brightness,
+        contrast,
+      } => brightness_contrast(pixels, *brightness, *contrast),
+      Operation::ReplaceColour {
+        target,
+        replacement,
+        tolerance,
+        softness,
+      } => replace_colour(pixels, target, replacement, *tolerance, *softness)?,
+      Operation::ChromaKey {
+        target,
+        tolerance,
+        softness,
+      } => chroma_key(pixels, target, *tolerance, *softness)?,
+    }
+    Ok(())
+  }
+
+  // `ImageData::data` hands back a copy, so the result is written into the live array afterwards.
+  #[cfg(feature = "wasm")]
+  pub fn apply_to_image_data(&self, image_data: &ImageData) -> Result<(), ErrorCode> {
+    let mut pixels = image_data.data().0;
+    self.apply(&mut pixels, image_data.width())?;
+    let data = js_sys::Reflect::get(image_data, &JsValue::from_str("data"))
+      .map_err(|_| ErrorCode::CanvasError)?;
+    js_sys::Uint8ClampedArray::from(data).copy_from(&pixels);
+    Ok(())
+  }
+
+  #[cfg(feature = "wasm")]
+  pub fn apply_to_canvas(&self, context: &CanvasRenderingContext2d) -> Result<(), ErrorCode> {
+    let canvas = context.canvas().ok_or(ErrorCode::CanvasError)?;
+    let (width, height) = (canvas.width() as f64, canvas.height() as f64);
+    let image_data = context
+      .get_image_data(0.0, 0.0, width, height)
+      .map_err(|_| ErrorCode::CanvasError)?;
+    self.apply_to_image_data(&image_data)?;
+    context
+      .put_image_data(&image_data, 0.0, 0.0)
+      .map_err(|_| ErrorCode::CanvasError)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PIXELS: [u8; 12] = [255, 0, 0, 255, 40, 200, 120, 128, 250, 250, 250, 0];
+
+  fn filtered(filter: Filter) -> Vec<u8> {
+    let mut pixels = PIXELS.to_vec();
+    filter.apply(&mut pixels, 3).unwrap();
+    pixels
+  }
+
+  mod apply {
+    use super::*;
+
+    #[test]
+    fn grayscale() {
+      let pixels = filtered(Filter::grayscale());
+      assert_eq!(&pixels[..4], &[127, 127, 127, 255]);
+      assert_eq!(pixels[4], pixels[5]);
+      assert_eq!(pixels[7], 128);
+      assert_eq!(&pixels[8..], &[250, 250, 250, 0]);
+    }
+
+    #[test]
+    fn simulate() {
+      let pixels = filtered(Filter::simulate(Deficiency::Protanopia, 1.0));
+      let expected = RgbColour::from_tuple(40, 200, 120).simulate(Deficiency::Protanopia, 1.0);
+      assert_eq!(
+        &pixels[4..8],
+        &[expected.red, expected.green, expected.blue, 128]
+      );
+    }
+
+    #[test]
+    fn remap() {
+      let palette = vec![
+        RgbColour::from_tuple(0, 0, 0),
+        RgbColour::from_tuple(255, 255, 255),
+        RgbColour::from_tuple(0, 160, 80),
+      ];
+      let pixels = filtered(Filter::remap(palette, Metric::Lab, Dithering::None));
+      assert_eq!(
+        pixels,
+        vec![255, 255, 255, 255, 0, 160, 80, 128, 255, 255, 255, 0]
+      );
+    }
+
+    #[test]
+    fn remap_partial_rows() {
+      let filter = Filter::remap(
+        vec![RgbColour::from_tuple(0, 0, 0)],
+        Metric::Lab,
+        Dithering::None,
+      );
+      assert_eq!(
+        filter.apply(&mut PIXELS.to_vec(), 2),
+        Err(ErrorCode::BufferSizeMismatch {
+          expected: 16,
+          found: 12
+        })
+      );
+      assert_eq!(
+        filter.apply(&mut PIXELS.to_vec(), 0),
+        Err(ErrorCode::BufferSizeMismatch {
+          expected: 0,
+          found: 12
+        })
+      );
+      assert_eq!(filter.apply(&mut [], 0), Ok(()));
+    }
+
+    #[test]
+    fn hue_rotate() {
+      assert_eq!(filtered(Filter::hue_rotate(0.0)), PIXELS.to_vec());
+      assert_eq!(filtered(Filter::hue_rotate(360.0)), PIXELS.to_vec());
+      let pixels = filtered(Filter::hue_rotate(120.0));
+      assert!(pixels[1] > pixels[0]);
+      assert_eq!(&pixels[8..], &[250, 250, 250, 0]);
+    }
+
+    #[test]
+    fn brightness_contrast() {
+      assert_eq!(
+        filtered(Filter::brightness_contrast(1.0, 1.0)),
+        PIXELS.to_vec()
+      );
+      assert_eq!(
+        &filtered(Filter::brightness_contrast(0.5, 1.0))[..4],
+        &[128, 0, 0, 255]
+      );
+      assert_eq!(
+        &filtered(Filter::brightness_contrast(1.0, 0.0))[4..8],
+        &[128, 128, 128, 128]
+      );
+    }
+
+    #[test]
+    fn replace_colour() {
+      let pixels = filtered(Filter::replace_colour(
+        &RgbColour::from_tuple(255, 255, 255),
+        &RgbColour::from_tuple(0, 0, 255),
+        5.0,
+        0.0,
+      ));
+      assert_eq!(&pixels[..8], &PIXELS[..8]);
+      assert_eq!(&pixels[8..], &[0, 0, 255, 0]);
+    }
+
+    #[test]
+    fn soft_edge() {
+      let mut pixels = vec![255, 255, 255, 255, 240, 240, 240, 255, 200, 200, 200, 255];
+      Filter::replace_colour(
+        &RgbColour::from_tuple(255, 255, 255),
+        &RgbColour::from_tuple(0, 0, 0),
+        2.0,
+        6.0,
+      )
+      .apply(&mut pixels, 3)
+      .unwrap();
+      assert_eq!(&pixels[..4], &[0, 0, 0, 255]);
+      assert!(pixels[4] > 0 && pixels[4] < 240, "{}", pixels[4]);
+      assert_eq!(&pixels[8..], &[200, 200, 200, 255]);
+    }
+
+    #[test]
+    fn chroma_key() {
+      let pixels = filtered(Filter::chroma_key(
+        &RgbColour::from_tuple(255, 0, 0),
+        10.0,
+        0.0,
+      ));
+      assert_eq!(&pixels[..4], &[255, 0, 0, 0]);
+      assert_eq!(&pixels[4..], &PIXELS[4..]);
+    }
+
+    #[test]
+    fn invalid_length() {
+      assert_eq!(
+        Filter::grayscale().apply(&mut [0; 5], 1),
+        Err(ErrorCode::InvalidBufferLength(5))
+      );
+    }
+  }
+
+  mod chroma_key_mask {
+    use super::*;
+
+    #[test]
+    fn falloff() {
+      let pixels = [255, 255, 255, 0, 240, 240, 240, 255, 200, 200, 200, 255];
+      let white = RgbColour::from_tuple(255, 255, 255);
+      let mask = chroma_key_mask(&pixels, &white, 2.0, 6.0).unwrap();
+      assert_eq!(mask[0], 0);
+      assert!(mask[1] > 0 && mask[1] < 255, "{}", mask[1]);
+      assert_eq!(mask[2], 255);
+      assert_eq!(
+        chroma_key_mask(&pixels, &white, 2.0, 0.0).unwrap(),
+        vec![0, 255, 255]
+      );
+    }
+
+    #[test]
+    fn invalid_length() {
+      assert_eq!(
+        chroma_key_mask(&[0; 3], &RgbColour::from_tuple(0, 0, 0), 1.0, 1.0),
+        Err(ErrorCode::InvalidBufferLength(3))
+      );
+    }
+  }
+}
@@ -0,0 +1,9 @@
+use crate::prelude::*;
+
+// Flattens (r, g, b, a) tuples into an RGBA buffer.
+pub fn image(colours: &[(u8, u8, u8, u8)]) -> Vec<u8> {
+  colours
+    .iter()
+    .flat_map(|&(r, g, b, a)| vec![r, g, b, a])
+    .collect()
+}
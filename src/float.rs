@@ -0,0 +1,395 @@
+use crate::colour::RgbColour;
+use crate::colour::XyzColour;
+use crate::colour::SRGB_TO_XYZ_64;
+use crate::colour::XYZ_TO_SRGB_64;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::matrix::apply;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum BitDepth {
+  Eight,
+  Ten,
+  Twelve,
+  Sixteen,
+}
+
+impl BitDepth {
+  fn max(self) -> f64 {
+    match self {
+      BitDepth::Eight => 255.0,
+      BitDepth::Ten => 1023.0,
+      BitDepth::Twelve => 4095.0,
+      BitDepth::Sixteen => 65535.0,
+    }
+  }
+}
+
+// Gamma-encoded sRGB with unbounded channels, nominally between 0 and 1.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct FloatRgbColour {
+  pub red: f64,
+  pub green: f64,
+  pub blue: f64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct LinearRgbColour {
+  pub red: f64,
+  pub green: f64,
+  pub blue: f64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct FloatHslColour {
+  pub hue: f64,
+  pub saturation: f64,
+  pub lightness: f64,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct QuantizedRgbColour {
+  pub red: u16,
+  pub green: u16,
+  pub blue: u16,
+  pub depth: BitDepth,
+}
+
+// Negative values mirror the curve, so out of range channels survive a round trip.
+fn decode(val: f64) -> f64 {
+  let abs = val.abs();
+  if abs > 0.04045 {
+    ((abs + 0.055) / 1.055).powf(2.4).copysign(val)
+  } else {
+    val / 12.92
+  }
+}
+
+fn encode(val: f64) -> f64 {
+  let abs = val.abs();
+  if abs > 0.003_130_8 {
+    (1.055 * abs.powf(1.0 / 2.4) - 0.055).copysign(val)
+  } else {
+    val * 12.92
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FloatRgbColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(red: f64, green: f64, blue: f64) -> FloatRgbColour {
+    FloatRgbColour { red, green, blue }
+  }
+
+  pub fn from_rgb(colour: &RgbColour) -> FloatRgbColour {
+    QuantizedRgbColour::new(
+      colour.red as u16,
+      colour.green as u16,
+      colour.blue as u16,
+      BitDepth::Eight,
+    )
+    .to_float()
+  }
+
+  pub fn from_hsl(colour: &FloatHslColour) -> FloatRgbColour {
+    let saturation = colour.saturation / 100.0;
+    let lightness = colour.lightness / 100.0;
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let channel = |n: f64| {
+      let k = (n + colour.hue.rem_euclid(360.0) / 30.0) % 12.0;
+      lightness - chroma / 2.0 * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+
+    FloatRgbColour {
+      red: channel(0.0),
+      green: channel(8.0),
+      blue: channel(4.0),
+    }
+  }
+
+  pub fn in_gamut(&self) -> bool {
+    [self.red, self.green, self.blue]
+      .iter()
+      .all(|channel| (0.0..=1.0).contains(channel))
+  }
+
+  pub fn clamp(&self) -> FloatRgbColour {
+    FloatRgbColour {
+      red: self.red.clamp(0.0, 1.0),
+      green: self.green.clamp(0.0, 1.0),
+      blue: self.blue.clamp(0.0, 1.0),
+    }
+  }
+
+  pub fn to_linear(&self) -> LinearRgbColour {
+    LinearRgbColour {
+      red: decode(self.red),
+      green: decode(self.green),
+      blue: decode(self.blue),
+    }
+  }
+
+  pub fn to_hsl(&self) -> FloatHslColour {
+    let max = self.red.max(self.green).max(self.blue);
+    let min = self.red.min(self.green).min(self.blue);
+    let chroma = max - min;
+    let lightness = (max + min) / 2.0;
+
+    let saturation = if chroma == 0.0 {
+      0.0
+    } else {
+      chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+    };
+
+    let hue = if chroma == 0.0 {
+      0.0
+    } else if max == self.red {
+      ((self.green - self.blue) / chroma).rem_euclid(6.0) * 60.0
+    } else if max == self.green {
+      ((self.blue - self.red) / chroma + 2.0) * 60.0
+    } else {
+      ((self.red - self.green) / chroma + 4.0) * 60.0
+    };
+
+    FloatHslColour {
+      hue,
+      saturation: saturation * 100.0,
+      lightness: lightness * 100.0,
+    }
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    self.to_linear().to_xyz()
+  }
+
+  pub fn quantize(&self, depth: BitDepth) -> QuantizedRgbColour {
+    let channel = |value: f64| (value.clamp(0.0, 1.0) * depth.max()).round() as u16;
+    QuantizedRgbColour {
+      red: channel(self.red),
+      green: channel(self.green),
+      blue: channel(self.blue),
+      depth,
+    }
+  }
+
+  pub fn to_rgb(&self) -> RgbColour {
+    let quantized = self.quantize(BitDepth::Eight);
+    RgbColour::from_tuple(
+      quantized.red as u8,
+      quantized.green as u8,
+      quantized.blue as u8,
+    )
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LinearRgbColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(red: f64, green: f64, blue: f64) -> LinearRgbColour {
+    LinearRgbColour { red, green, blue }
+  }
+
+  pub fn from_xyz(xyz: &XyzColour) -> LinearRgbColour {
+    let (red, green, blue) = apply(&XYZ_TO_SRGB_64, (xyz.x as f64, xyz.y as f64, xyz.z as f64));
+    LinearRgbColour { red, green, blue }
+  }
+
+  pub fn luminance(&self) -> f64 {
+    apply(&SRGB_TO_XYZ_64, (self.red, self.green, self.blue)).1
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    let (x, y, z) = apply(&SRGB_TO_XYZ_64, (self.red, self.green, self.blue));
+    XyzColour::new(x as f32, y as f32, z as f32)
+  }
+
+  pub fn to_encoded(&self) -> FloatRgbColour {
+    FloatRgbColour {
+      red: encode(self.red),
+      green: encode(self.green),
+      blue: encode(self.blue),
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FloatHslColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(hue: f64, saturation: f64, lightness: f64) -> FloatHslColour {
+    FloatHslColour {
+      hue,
+      saturation,
+      lightness,
+    }
+  }
+
+  pub fn to_rgb(&self) -> FloatRgbColour {
+    FloatRgbColour::from_hsl(self)
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl QuantizedRgbColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(red: u16, green: u16, blue: u16, depth: BitDepth) -> QuantizedRgbColour {
+    QuantizedRgbColour {
+      red,
+      green,
+      blue,
+      depth,
+    }
+  }
+
+  pub fn to_float(&self) -> FloatRgbColour {
+    let max = self.depth.max();
+    FloatRgbColour {
+      red: (self.red as f64 / max).min(1.0),
+      green: (self.green as f64 / max).min(1.0),
+      blue: (self.blue as f64 / max).min(1.0),
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl RgbColour {
+  pub fn to_float(&self) -> FloatRgbColour {
+    FloatRgbColour::from_rgb(self)
+  }
+}
+
+impl core::convert::From<RgbColour> for FloatRgbColour {
+  fn from(colour: RgbColour) -> Self {
+    FloatRgbColour::from_rgb(&colour)
+  }
+}
+
+impl core::convert::From<FloatRgbColour> for (f64, f64, f64) {
+  fn from(colour: FloatRgbColour) -> Self {
+    (colour.red, colour.green, colour.blue)
+  }
+}
+
+impl core::convert::From<FloatHslColour> for (f64, f64, f64) {
+  fn from(colour: FloatHslColour) -> Self {
+    (colour.hue, colour.saturation, colour.lightness)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(actual: (f64, f64, f64), expected: (f64, f64, f64)) {
+    assert!(
+      (actual.0 - expected.0).abs() < 1e-9
+        && (actual.1 - expected.1).abs() < 1e-9
+        && (actual.2 - expected.2).abs() < 1e-9,
+      "{:?} != {:?}",
+      actual,
+      expected
+    );
+  }
+
+  mod hsl {
+    use super::*;
+
+    #[test]
+    fn fractional_hue() {
+      let hsl = FloatRgbColour::new(1.0, 0.01, 0.0).to_hsl();
+      assert!((hsl.hue - 0.6).abs() < 1e-9);
+      assert!((hsl.saturation - 100.0).abs() < 1e-9);
+      assert!((hsl.lightness - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rebeccapurple() {
+      let hsl = FloatRgbColour::from_rgb(&RgbColour::from_tuple(102, 51, 153)).to_hsl();
+      assert_close(hsl.into(), (270.0, 50.0, 40.0));
+    }
+
+    #[test]
+    fn round_trip() {
+      let colour = FloatRgbColour::new(0.123_456, 0.654_321, 0.987_654);
+      let mut result = colour;
+      for _ in 0..100 {
+        result = result.to_hsl().to_rgb();
+      }
+      assert_close(result.into(), colour.into());
+    }
+  }
+
+  mod linear {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+      let colour = FloatRgbColour::new(0.5, -0.1, 1.2);
+      assert_close(colour.to_linear().to_encoded().into(), colour.into());
+    }
+
+    #[test]
+    fn white_luminance() {
+      let white = FloatRgbColour::new(1.0, 1.0, 1.0).to_linear();
+      assert!((white.luminance() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn matches_rgb_to_xyz() {
+      for colour in [
+        RgbColour::from_tuple(255, 255, 255),
+        RgbColour::from_tuple(102, 51, 153),
+        RgbColour::from_tuple(244, 43, 32),
+        RgbColour::from_tuple(3, 43, 234),
+      ] {
+        let float = FloatRgbColour::from_rgb(&colour).to_xyz();
+        let eight = colour.to_xyz();
+        assert!(
+          (float.x - eight.x).abs() < 1e-5
+            && (float.y - eight.y).abs() < 1e-5
+            && (float.z - eight.z).abs() < 1e-5,
+          "{:?} != {:?}",
+          float,
+          eight
+        );
+      }
+    }
+  }
+
+  mod quantize {
+    use super::*;
+
+    #[test]
+    fn bit_depths() {
+      let colour = FloatRgbColour::new(0.5, 1.0, 0.0);
+      assert_eq!(
+        colour.quantize(BitDepth::Ten),
+        QuantizedRgbColour::new(512, 1023, 0, BitDepth::Ten)
+      );
+      assert_eq!(colour.quantize(BitDepth::Sixteen).red, 32768);
+      assert_eq!(colour.to_rgb(), RgbColour::from_tuple(128, 255, 0));
+    }
+
+    #[test]
+    fn clamps_out_of_range() {
+      let colour = FloatRgbColour::new(-0.2, 1.5, 0.25);
+      assert!(!colour.in_gamut());
+      assert_eq!(
+        colour.quantize(BitDepth::Twelve),
+        QuantizedRgbColour::new(0, 4095, 1024, BitDepth::Twelve)
+      );
+    }
+
+    #[test]
+    fn eight_bit_round_trip() {
+      let colour = RgbColour::from_tuple(244, 43, 32);
+      assert_eq!(colour.to_float().to_hsl().to_rgb().to_rgb(), colour);
+    }
+  }
+}
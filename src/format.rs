@@ -0,0 +1,401 @@
+use crate::colour::HslColour;
+use crate::colour::LabColour;
+use crate::colour::RgbColour;
+use crate::colour_space::format_number;
+use crate::error_code::ErrorCode;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::prelude::*;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Notation {
+  Hex,
+  Css,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct FormatOptions {
+  pub notation: Notation,
+  pub prefix: bool,
+  pub uppercase: bool,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl FormatOptions {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new() -> FormatOptions {
+    FormatOptions {
+      notation: Notation::Hex,
+      prefix: true,
+      uppercase: false,
+    }
+  }
+
+  pub fn hex(prefix: bool, uppercase: bool) -> FormatOptions {
+    FormatOptions {
+      notation: Notation::Hex,
+      prefix,
+      uppercase,
+    }
+  }
+
+  pub fn css() -> FormatOptions {
+    FormatOptions {
+      notation: Notation::Css,
+      ..FormatOptions::new()
+    }
+  }
+}
+
+impl Default for FormatOptions {
+  fn default() -> Self {
+    FormatOptions::new()
+  }
+}
+
+fn hex(colour: &RgbColour, options: &FormatOptions) -> String {
+  let hex = if options.uppercase {
+    colour.to_hex().to_uppercase()
+  } else {
+    colour.to_hex()
+  };
+
+  if options.prefix {
+    format!("#{}", hex)
+  } else {
+    hex
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl RgbColour {
+  pub fn format(&self, options: &FormatOptions) -> String {
+    match options.notation {
+      Notation::Hex => hex(self, options),
+      Notation::Css => format!("rgb({} {} {})", self.red, self.green, self.blue),
+    }
+  }
+
+  // Falls back to black when the value cannot be parsed.
+  pub fn from_str_lossy(value: &str) -> RgbColour {
+    RgbColour::parse(value).unwrap_or_else(|_| RgbColour::from_tuple(0, 0, 0))
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl HslColour {
+  pub fn format(&self, options: &FormatOptions) -> String {
+    match options.notation {
+      Notation::Hex => hex(&self.to_rgb(), options),
+      Notation::Css => {
+        let (hue, saturation, lightness) = (*self).into();
+        format!(
+          "hsl({} {}% {}%)",
+          hue,
+          format_number(saturation, 2),
+          format_number(lightness, 2)
+        )
+      }
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LabColour {
+  pub fn format(&self, options: &FormatOptions) -> String {
+    match options.notation {
+      Notation::Hex => hex(&self.to_rgb(), options),
+      Notation::Css => {
+        let (lightness, a, b) = (*self).into();
+        format!(
+          "lab({} {} {})",
+          format_number(lightness, 5),
+          format_number(a, 5),
+          format_number(b, 5)
+        )
+      }
+    }
+  }
+}
+
+impl fmt::Display for RgbColour {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.format(&FormatOptions::new()))
+  }
+}
+
+impl fmt::Display for HslColour {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.format(&FormatOptions::css()))
+  }
+}
+
+impl fmt::Display for LabColour {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.format(&FormatOptions::css()))
+  }
+}
+
+pub(crate) enum CssColour {
+  Rgb(RgbColour),
+  Hsl(HslColour),
+  Lab(LabColour),
+}
+
+impl CssColour {
+  pub(crate) fn to_rgb(&self) -> RgbColour {
+    match self {
+      CssColour::Rgb(colour) => *colour,
+      CssColour::Hsl(colour) => colour.to_rgb(),
+      CssColour::Lab(colour) => colour.to_rgb(),
+    }
+  }
+}
+
+pub(crate) fn is_css_function(value: &str) -> bool {
+  value.trim_end().ends_with(')')
+}
+
+// Parses `rgb()`, `hsl()` and `lab()` in both the legacy comma and modern space separated forms.
+pub(crate) fn parse_css_function(value: &str) -> Result<CssColour, ErrorCode> {
+  let error = |position: usize, expected: &str| ErrorCode::InvalidCssFunction {
+    value: value.to_string(),
+    position,
+    expected: expected.to_string(),
+  };
+
+  let open = value.find('(').ok_or_else(|| error(0, "("))?;
+  let close = value.rfind(')').ok_or_else(|| error(value.len(), ")"))?;
+  let name = value[..open].trim().to_ascii_lowercase();
+
+  let mut arguments = vec![];
+  let mut start = None;
+  for (index, character) in value[open + 1..close].char_indices() {
+    let index = index + open + 1;
+    if character == ',' || character == '/' || character.is_whitespace() {
+      if let Some(start) = start.take() {
+        arguments.push((start, &value[start..index]));
+      }
+    } else if start.is_none() {
+      start = Some(index);
+    }
+  }
+  if let Some(start) = start {
+    arguments.push((start, &value[start..close]));
+  }
+
+  // Alpha is accepted as a fourth argument, but colours here are opaque.
+  if arguments.len() != 3 && arguments.len() != 4 {
+    return Err(error(close, "3 components"));
+  }
+
+  let number = |(position, argument): (usize, &str), percentage: f32, expected: &str| {
+    let (digits, scale) = match argument.strip_suffix('%') {
+      Some(digits) => (digits, percentage / 100.0),
+      None => (argument.strip_suffix("deg").unwrap_or(argument), 1.0),
+    };
+    digits
+      .parse::<f32>()
+      .ok()
+      .filter(|number| number.is_finite())
+      .map(|number| number * scale)
+      .ok_or_else(|| error(position, expected))
+  };
+
+  match name.as_str() {
+    "rgb" | "rgba" => {
+      let channel = |index: usize| {
+        number(arguments[index], 255.0, "a number or percentage")
+          .map(|channel| channel.clamp(0.0, 255.0).round() as u8)
+      };
+      Ok(CssColour::Rgb(RgbColour::from_tuple(
+        channel(0)?,
+        channel(1)?,
+        channel(2)?,
+      )))
+    }
+    "hsl" | "hsla" => {
+      let hue = number(arguments[0], 360.0, "a hue")?;
+      let saturation = number(arguments[1], 100.0, "a percentage")?;
+      let lightness = number(arguments[2], 100.0, "a percentage")?;
+      Ok(CssColour::Hsl(HslColour::new(
+        (hue.round() as i32).rem_euclid(360) as u32,
+        saturation.clamp(0.0, 100.0),
+        lightness.clamp(0.0, 100.0),
+      )))
+    }
+    "lab" => Ok(CssColour::Lab(LabColour::new(
+      number(arguments[0], 100.0, "a lightness")?,
+      number(arguments[1], 125.0, "a number or percentage")?,
+      number(arguments[2], 125.0, "a number or percentage")?,
+    ))),
+    _ => Err(error(0, "rgb, hsl or lab")),
+  }
+}
+
+impl FromStr for RgbColour {
+  type Err = ErrorCode;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    RgbColour::parse(value)
+  }
+}
+
+impl FromStr for HslColour {
+  type Err = ErrorCode;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    if !is_css_function(value) {
+      return RgbColour::parse(value).map(HslColour::from);
+    }
+
+    match parse_css_function(value)? {
+      CssColour::Hsl(colour) => Ok(colour),
+      colour => Ok(HslColour::from(colour.to_rgb())),
+    }
+  }
+}
+
+impl FromStr for LabColour {
+  type Err = ErrorCode;
+
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    if !is_css_function(value) {
+      return RgbColour::parse(value).map(LabColour::from);
+    }
+
+    match parse_css_function(value)? {
+      CssColour::Lab(colour) => Ok(colour),
+      colour => Ok(LabColour::from(colour.to_rgb())),
+    }
+  }
+}
+
+impl TryFrom<&str> for RgbColour {
+  type Error = ErrorCode;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    value.parse()
+  }
+}
+
+impl TryFrom<&str> for HslColour {
+  type Error = ErrorCode;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    value.parse()
+  }
+}
+
+impl TryFrom<&str> for LabColour {
+  type Error = ErrorCode;
+
+  fn try_from(value: &str) -> Result<Self, Self::Error> {
+    value.parse()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  mod display {
+    use super::*;
+
+    #[test]
+    fn rgb() {
+      let colour = RgbColour::from_tuple(244, 43, 32);
+      assert_eq!(colour.to_string(), "#f42b20");
+      assert_eq!(colour.format(&FormatOptions::hex(false, true)), "F42B20");
+      assert_eq!(colour.format(&FormatOptions::css()), "rgb(244 43 32)");
+    }
+
+    #[test]
+    fn hsl() {
+      let colour = HslColour::from(RgbColour::from_tuple(102, 51, 153));
+      assert_eq!(colour.to_string(), "hsl(270 50% 40%)");
+      assert_eq!(colour.format(&FormatOptions::new()), "#663399");
+    }
+
+    #[test]
+    fn lab() {
+      let colour = LabColour::new(50.0, -20.5, 10.25);
+      assert_eq!(colour.to_string(), "lab(50 -20.5 10.25)");
+    }
+  }
+
+  mod from_str {
+    use super::*;
+
+    #[test]
+    fn rgb() {
+      let expected = Ok(RgbColour::from_tuple(102, 51, 153));
+      assert_eq!("#663399".parse::<RgbColour>(), expected);
+      assert_eq!("RebeccaPurple".parse::<RgbColour>(), expected);
+      assert_eq!("rgb(102, 51, 153)".parse::<RgbColour>(), expected);
+      assert_eq!("rgb(40% 20% 60% / 0.5)".parse::<RgbColour>(), expected);
+      assert_eq!(RgbColour::try_from("hsl(270deg 50% 40%)"), expected);
+    }
+
+    #[test]
+    fn hsl() {
+      assert_eq!(
+        "hsl(270, 50%, 40%)".parse::<HslColour>(),
+        Ok(HslColour::new(270, 50.0, 40.0))
+      );
+      assert_eq!(
+        HslColour::try_from("#fff"),
+        Ok(HslColour::new(0, 0.0, 100.0))
+      );
+    }
+
+    #[test]
+    fn lab() {
+      assert_eq!(
+        "lab(50% -20 10)".parse::<LabColour>(),
+        Ok(LabColour::new(50.0, -20.0, 10.0))
+      );
+    }
+
+    #[test]
+    fn round_trip() {
+      let colour = RgbColour::from_tuple(12, 200, 99);
+      assert_eq!(colour.to_string().parse(), Ok(colour));
+      assert_eq!(colour.format(&FormatOptions::css()).parse(), Ok(colour));
+    }
+
+    #[test]
+    fn invalid() {
+      assert_eq!(
+        "rgb(10, x, 30)".parse::<RgbColour>(),
+        Err(ErrorCode::InvalidCssFunction {
+          value: "rgb(10, x, 30)".to_string(),
+          position: 8,
+          expected: "a number or percentage".to_string(),
+        })
+      );
+      assert!("hwb(10 20% 30%)".parse::<RgbColour>().is_err());
+      assert!("rgb(1 2)".parse::<RgbColour>().is_err());
+      assert!(LabColour::try_from("rust").is_err());
+    }
+  }
+
+  mod from_str_lossy {
+    use super::*;
+
+    #[test]
+    fn invalid_is_black() {
+      assert_eq!(
+        RgbColour::from_str_lossy("F43C8X"),
+        RgbColour::from_tuple(0, 0, 0)
+      );
+    }
+  }
+}
@@ -0,0 +1,261 @@
+use crate::colour::RgbColour;
+use crate::colour::XyzColour;
+use crate::colour_space::ColourSpace;
+use crate::colour_space::SpaceColour;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::oklab::OklabColour;
+use crate::oklab::OklchColour;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Just noticeable difference in deltaEOK, as used by CSS Color 4.
+pub const JND: f32 = 0.02;
+const EPSILON: f32 = 0.0001;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum GamutMapping {
+  // CSS Color 4 gamut mapping: OKLCh chroma reduction with a deltaEOK tolerance.
+  Css,
+  // Chroma reduction in OKLCh until the colour is inside the gamut, keeping lightness and hue.
+  ReduceChroma,
+  // Clamps each channel of the destination space, which CSS Color 4 calls clipping.
+  Clamp,
+}
+
+pub(crate) fn xyz_in_gamut(xyz: &XyzColour, space: &ColourSpace) -> bool {
+  let (red, green, blue) = space.xyz_to_linear(xyz);
+  [red, green, blue]
+    .iter()
+    .all(|channel| *channel >= -EPSILON && *channel <= 1.0 + EPSILON)
+}
+
+fn clamp(colour: SpaceColour) -> SpaceColour {
+  SpaceColour {
+    red: colour.red.clamp(0.0, 1.0),
+    green: colour.green.clamp(0.0, 1.0),
+    blue: colour.blue.clamp(0.0, 1.0),
+    ..colour
+  }
+}
+
+fn css(origin: OklchColour, space: &ColourSpace) -> SpaceColour {
+  let clipped = clamp(space.from_xyz(&origin.to_xyz()));
+  let clipped_oklab = |clipped: &SpaceColour| OklabColour::from_xyz(&clipped.to_xyz());
+  if clipped_oklab(&clipped).delta_e(&origin.to_oklab()) < JND {
+    return clipped;
+  }
+
+  let mut current = origin;
+  let mut clipped = clipped;
+  let mut min = 0.0;
+  let mut max = origin.chroma;
+  let mut min_in_gamut = true;
+
+  while max - min > EPSILON {
+    current.chroma = (min + max) / 2.0;
+    let xyz = current.to_xyz();
+
+    if min_in_gamut && xyz_in_gamut(&xyz, space) {
+      min = current.chroma;
+      continue;
+    }
+
+    clipped = clamp(space.from_xyz(&xyz));
+    let delta_e = clipped_oklab(&clipped).delta_e(&current.to_oklab());
+    if delta_e < JND {
+      if JND - delta_e < EPSILON {
+        return clipped;
+      }
+      min_in_gamut = false;
+      min = current.chroma;
+    } else {
+      max = current.chroma;
+    }
+  }
+
+  clipped
+}
+
+fn reduce_chroma(origin: OklchColour, space: &ColourSpace) -> SpaceColour {
+  let mut current = origin;
+  let mut min = 0.0;
+  let mut max = origin.chroma;
+
+  while max - min > EPSILON {
+    current.chroma = (min + max) / 2.0;
+    if xyz_in_gamut(&current.to_xyz(), space) {
+      min = current.chroma;
+    } else {
+      max = current.chroma;
+    }
+  }
+
+  current.chroma = min;
+  clamp(space.from_xyz(&current.to_xyz()))
+}
+
+pub fn map_to_gamut(xyz: &XyzColour, space: &ColourSpace, mapping: GamutMapping) -> SpaceColour {
+  if mapping == GamutMapping::Clamp {
+    return clamp(space.from_xyz(xyz));
+  }
+
+  let origin = OklabColour::from_xyz(xyz).to_lch();
+  if origin.lightness >= 1.0 {
+    return SpaceColour::new(space, 1.0, 1.0, 1.0);
+  }
+  if origin.lightness <= 0.0 {
+    return SpaceColour::new(space, 0.0, 0.0, 0.0);
+  }
+  if xyz_in_gamut(xyz, space) {
+    return space.from_xyz(xyz);
+  }
+
+  match mapping {
+    GamutMapping::Css => css(origin, space),
+    _ => reduce_chroma(origin, space),
+  }
+}
+
+fn to_rgb(colour: &SpaceColour) -> RgbColour {
+  let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+  RgbColour::from_tuple(
+    channel(colour.red),
+    channel(colour.green),
+    channel(colour.blue),
+  )
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl SpaceColour {
+  pub fn in_gamut(&self, space: &ColourSpace) -> bool {
+    xyz_in_gamut(&self.to_xyz(), space)
+  }
+
+  pub fn to_space_mapped(&self, space: &ColourSpace, mapping: GamutMapping) -> SpaceColour {
+    map_to_gamut(&self.to_xyz(), space, mapping)
+  }
+
+  pub fn to_rgb_mapped(&self, mapping: GamutMapping) -> RgbColour {
+    to_rgb(&self.to_space_mapped(&ColourSpace::srgb(), mapping))
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl OklabColour {
+  pub fn in_gamut(&self, space: &ColourSpace) -> bool {
+    xyz_in_gamut(&self.to_xyz(), space)
+  }
+
+  pub fn to_space(&self, space: &ColourSpace, mapping: GamutMapping) -> SpaceColour {
+    map_to_gamut(&self.to_xyz(), space, mapping)
+  }
+
+  pub fn to_rgb(&self, mapping: GamutMapping) -> RgbColour {
+    to_rgb(&self.to_space(&ColourSpace::srgb(), mapping))
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl OklchColour {
+  pub fn in_gamut(&self, space: &ColourSpace) -> bool {
+    xyz_in_gamut(&self.to_xyz(), space)
+  }
+
+  pub fn to_space(&self, space: &ColourSpace, mapping: GamutMapping) -> SpaceColour {
+    map_to_gamut(&self.to_xyz(), space, mapping)
+  }
+
+  pub fn to_rgb(&self, mapping: GamutMapping) -> RgbColour {
+    to_rgb(&self.to_space(&ColourSpace::srgb(), mapping))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn p3_red() -> SpaceColour {
+    SpaceColour::new(&ColourSpace::display_p3(), 1.0, 0.0, 0.0)
+  }
+
+  fn channels(colour: &SpaceColour) -> (f32, f32, f32) {
+    (colour.red, colour.green, colour.blue)
+  }
+
+  mod in_gamut {
+    use super::*;
+
+    #[test]
+    fn srgb_inside_display_p3() {
+      let colour = SpaceColour::from_rgb(&RgbColour::from_tuple(255, 0, 0));
+      assert!(colour.in_gamut(&ColourSpace::display_p3()));
+      assert!(colour.in_gamut(&ColourSpace::srgb()));
+    }
+
+    #[test]
+    fn display_p3_outside_srgb() {
+      assert!(!p3_red().in_gamut(&ColourSpace::srgb()));
+      let p3_pink = SpaceColour::new(&ColourSpace::display_p3(), 1.0, 0.3, 0.3);
+      assert!(!p3_pink.in_gamut(&ColourSpace::srgb()));
+      assert!(p3_pink.in_gamut(&ColourSpace::rec2020()));
+    }
+
+    #[test]
+    fn oklch() {
+      assert!(OklchColour::new(0.7, 0.05, 200.0).in_gamut(&ColourSpace::srgb()));
+      assert!(!OklchColour::new(0.7, 0.4, 200.0).in_gamut(&ColourSpace::srgb()));
+    }
+  }
+
+  mod map_to_gamut {
+    use super::*;
+
+    #[test]
+    fn in_gamut_colours_are_untouched() {
+      let colour = RgbColour::from_tuple(102, 51, 153);
+      assert_eq!(
+        SpaceColour::from_rgb(&colour).to_rgb_mapped(GamutMapping::Css),
+        colour
+      );
+    }
+
+    #[test]
+    fn clamp() {
+      let mapped = p3_red().to_space_mapped(&ColourSpace::srgb(), GamutMapping::Clamp);
+      assert_eq!(channels(&mapped), (1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn css() {
+      let origin = OklabColour::from_xyz(&p3_red().to_xyz());
+      let mapped = p3_red().to_space_mapped(&ColourSpace::srgb(), GamutMapping::Css);
+      let result = OklabColour::from_xyz(&mapped.to_xyz());
+
+      assert!(mapped.in_gamut(&ColourSpace::srgb()));
+      assert!((result.to_lch().hue - origin.to_lch().hue).abs() < 2.0);
+      assert!((result.lightness - origin.lightness).abs() < JND);
+      assert!(result.to_lch().chroma < origin.to_lch().chroma);
+    }
+
+    #[test]
+    fn reduce_chroma_keeps_lightness_and_hue() {
+      let origin = OklchColour::new(0.7, 0.4, 200.0);
+      let mapped = origin.to_space(&ColourSpace::srgb(), GamutMapping::ReduceChroma);
+      let result = OklabColour::from_xyz(&mapped.to_xyz()).to_lch();
+
+      assert!(mapped.in_gamut(&ColourSpace::srgb()));
+      assert!((result.lightness - origin.lightness).abs() < 0.001);
+      assert!((result.hue - origin.hue).abs() < 0.5);
+    }
+
+    #[test]
+    fn very_light_and_very_dark() {
+      let white = OklchColour::new(1.2, 0.3, 100.0).to_rgb(GamutMapping::Css);
+      let black = OklchColour::new(-0.1, 0.3, 100.0).to_rgb(GamutMapping::Css);
+      assert_eq!(white, RgbColour::from_tuple(255, 255, 255));
+      assert_eq!(black, RgbColour::from_tuple(0, 0, 0));
+    }
+  }
+}
@@ -0,0 +1,251 @@
+use crate::colour::RgbColour;
+use crate::contrast::contrast_ratio;
+use crate::oklab::OklchColour;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+const LIGHTNESS_STEP: f32 = 0.005;
+const CHROMA_SEARCH_STEPS: u32 = 24;
+// Backgrounds brighter than this contrast more with black than with white.
+const MID_LUMINANCE: f32 = 0.179_128_8;
+const EPSILON: f32 = 0.000_001;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct HashColourOptions {
+  // OKLCh lightness from 0 to 1 and chroma from 0 to about 0.37.
+  pub lightness: f32,
+  pub chroma: f32,
+  pub background: RgbColour,
+  // WCAG contrast ratio against the background, from 1 to 21.
+  pub min_contrast: f32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl HashColourOptions {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new() -> HashColourOptions {
+    HashColourOptions {
+      lightness: 0.65,
+      chroma: 0.12,
+      background: RgbColour::from_tuple(255, 255, 255),
+      min_contrast: 3.0,
+    }
+  }
+}
+
+impl Default for HashColourOptions {
+  fn default() -> Self {
+    HashColourOptions::new()
+  }
+}
+
+// FNV-1a followed by the SplitMix64 finaliser, so similar IDs still land far apart.
+fn hash(bytes: &[u8]) -> u64 {
+  let mut hash = bytes.iter().fold(FNV_OFFSET, |hash, byte| {
+    (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+  });
+  hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+  hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+  hash ^ (hash >> 31)
+}
+
+// Hashed colours have to be identical in native and wasm builds, so this path avoids the std float
+// methods, which may round differently per platform: `OklchColour::to_oklab` uses libm `cosf` and
+// `sinf`, `OklabColour::to_xyz` and `XyzColour::to_linear_rgb` only multiply and add,
+// `RgbColour::from_linear` encodes with libm `powf`, and `contrast_ratio` decodes through the
+// precomputed `SRGB_TO_LINEAR` table. `tests::colour_from_id::pinned` guards the results.
+fn linear_srgb(lightness: f32, chroma: f32, hue: f32) -> (f32, f32, f32) {
+  OklchColour::new(lightness, chroma, hue)
+    .to_xyz()
+    .to_linear_rgb()
+}
+
+fn in_gamut(linear: (f32, f32, f32)) -> bool {
+  [linear.0, linear.1, linear.2]
+    .iter()
+    .all(|channel| (-EPSILON..=1.0 + EPSILON).contains(channel))
+}
+
+fn encode(linear: (f32, f32, f32)) -> RgbColour {
+  RgbColour::from_linear(linear.0, linear.1, linear.2)
+}
+
+// Reduces chroma until the colour fits in sRGB, keeping lightness and hue.
+fn fit(lightness: f32, chroma: f32, hue: f32) -> RgbColour {
+  let linear = linear_srgb(lightness, chroma, hue);
+  if in_gamut(linear) {
+    return encode(linear);
+  }
+
+  let (mut low, mut high) = (0.0, chroma);
+  for _ in 0..CHROMA_SEARCH_STEPS {
+    let middle = (low + high) / 2.0;
+    if in_gamut(linear_srgb(lightness, middle, hue)) {
+      low = middle;
+    } else {
+      high = middle;
+    }
+  }
+  encode(linear_srgb(lightness, low, hue))
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn colour_from_bytes(bytes: &[u8], options: &HashColourOptions) -> RgbColour {
+  let hue = (hash(bytes) >> 40) as f32 / (1u64 << 24) as f32 * 360.0;
+  let step = if options.background.relative_luminance() > MID_LUMINANCE {
+    -LIGHTNESS_STEP
+  } else {
+    LIGHTNESS_STEP
+  };
+
+  // Walks lightness away from the background until the contrast is met, or black or white is reached.
+  let mut lightness = options.lightness.clamp(0.0, 1.0);
+  loop {
+    let colour = fit(lightness, options.chroma.max(0.0), hue);
+    if contrast_ratio(&colour, &options.background) >= options.min_contrast {
+      return colour;
+    }
+
+    lightness += step;
+    if !(0.0..=1.0).contains(&lightness) {
+      return if step < 0.0 {
+        RgbColour::from_tuple(0, 0, 0)
+      } else {
+        RgbColour::from_tuple(255, 255, 255)
+      };
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn colour_from_id(id: &str, options: &HashColourOptions) -> RgbColour {
+  colour_from_bytes(id.as_bytes(), options)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  mod colour_from_id {
+    use super::*;
+
+    #[test]
+    fn stable() {
+      let options = HashColourOptions::new();
+      assert_eq!(hash(b"user-1"), 0xc499_b5f8_a721_df58);
+      assert_eq!(
+        colour_from_id("user-1", &options),
+        RgbColour::from_tuple(124, 135, 216)
+      );
+      assert_eq!(
+        colour_from_id("user-1", &options),
+        colour_from_bytes(b"user-1", &options)
+      );
+      assert_ne!(
+        colour_from_id("user-1", &options),
+        colour_from_id("user-2", &options)
+      );
+    }
+
+    #[test]
+    fn pinned() {
+      let ids = ["user-1", "alice", "bob", "#general", "42"];
+      for (background, expected) in [
+        (
+          (255, 255, 255),
+          [
+            (101, 111, 189),
+            (105, 112, 190),
+            (36, 134, 82),
+            (0, 132, 133),
+            (0, 127, 170),
+          ],
+        ),
+        (
+          (0, 0, 0),
+          [
+            (124, 135, 216),
+            (127, 135, 215),
+            (72, 165, 111),
+            (0, 164, 165),
+            (39, 155, 202),
+          ],
+        ),
+        (
+          (128, 128, 128),
+          [
+            (18, 8, 80),
+            (20, 7, 80),
+            (0, 28, 12),
+            (0, 27, 27),
+            (0, 26, 38),
+          ],
+        ),
+      ] {
+        let options = HashColourOptions {
+          background: RgbColour::from_tuple(background.0, background.1, background.2),
+          min_contrast: 4.5,
+          ..HashColourOptions::new()
+        };
+        for (id, colour) in ids.iter().zip(expected) {
+          assert_eq!(
+            colour_from_id(id, &options),
+            RgbColour::from_tuple(colour.0, colour.1, colour.2),
+            "{} on {:?}",
+            id,
+            background
+          );
+        }
+      }
+    }
+
+    #[test]
+    fn contrast_guarantee() {
+      for background in [
+        RgbColour::from_tuple(255, 255, 255),
+        RgbColour::from_tuple(0, 0, 0),
+        RgbColour::from_tuple(30, 30, 46),
+      ] {
+        let options = HashColourOptions {
+          background,
+          min_contrast: 4.5,
+          ..HashColourOptions::new()
+        };
+        for id in 0..200 {
+          let colour = colour_from_id(&id.to_string(), &options);
+          assert!(contrast_ratio(&colour, &background) >= 4.49, "{:?}", colour);
+        }
+      }
+    }
+
+    #[test]
+    fn unreachable_contrast() {
+      let options = HashColourOptions {
+        min_contrast: 25.0,
+        ..HashColourOptions::new()
+      };
+      assert_eq!(
+        colour_from_id("anything", &options),
+        RgbColour::from_tuple(0, 0, 0)
+      );
+    }
+
+    #[test]
+    fn hues_spread() {
+      let options = HashColourOptions {
+        min_contrast: 1.0,
+        ..HashColourOptions::new()
+      };
+      let mut buckets = [0; 6];
+      for id in 0..600 {
+        let colour = colour_from_id(&format!("tag-{}", id), &options);
+        let hue = <(f32, f32, f32)>::from(colour.to_hsl()).0;
+        buckets[(hue / 60.0) as usize % 6] += 1;
+      }
+      assert!(buckets.iter().all(|count| *count > 50), "{:?}", buckets);
+    }
+  }
+}
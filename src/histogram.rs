@@ -0,0 +1,285 @@
+use crate::colour::HslColour;
+use crate::colour::LabColour;
+use crate::colour::RgbColour;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Offset used when taking the log of luminance so that black pixels stay finite.
+const LOG_DELTA: f32 = 0.0001;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Histogram {
+  red: Vec<u32>,
+  green: Vec<u32>,
+  blue: Vec<u32>,
+  alpha: Vec<u32>,
+  hue: Vec<u32>,
+  saturation: Vec<u32>,
+  lightness: Vec<u32>,
+  lab_lightness: Vec<u32>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Histogram {
+  pub fn red(&self) -> Vec<u32> {
+    self.red.clone()
+  }
+
+  pub fn green(&self) -> Vec<u32> {
+    self.green.clone()
+  }
+
+  pub fn blue(&self) -> Vec<u32> {
+    self.blue.clone()
+  }
+
+  pub fn alpha(&self) -> Vec<u32> {
+    self.alpha.clone()
+  }
+
+  pub fn hue(&self) -> Vec<u32> {
+    self.hue.clone()
+  }
+
+  pub fn saturation(&self) -> Vec<u32> {
+    self.saturation.clone()
+  }
+
+  pub fn lightness(&self) -> Vec<u32> {
+    self.lightness.clone()
+  }
+
+  pub fn lab_lightness(&self) -> Vec<u32> {
+    self.lab_lightness.clone()
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Statistics {
+  pub mean: f32,
+  pub median: f32,
+  pub variance: f32,
+  pub colourfulness: f32,
+  pub contrast: f32,
+  pub key: f32,
+}
+
+fn visible_pixels(pixels: &[u8]) -> impl Iterator<Item = RgbColour> + '_ {
+  pixels
+    .chunks_exact(4)
+    .filter(|pixel| pixel[3] > 0)
+    .map(|pixel| RgbColour::from_tuple(pixel[0], pixel[1], pixel[2]))
+}
+
+fn bin(value: f32, bins: usize) -> usize {
+  (value.round().max(0.0) as usize).min(bins - 1)
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn histogram(pixels: &[u8]) -> Histogram {
+  let mut histogram = Histogram {
+    red: vec![0; 256],
+    green: vec![0; 256],
+    blue: vec![0; 256],
+    alpha: vec![0; 256],
+    hue: vec![0; 360],
+    saturation: vec![0; 101],
+    lightness: vec![0; 101],
+    lab_lightness: vec![0; 101],
+  };
+
+  for pixel in pixels.chunks_exact(4) {
+    histogram.alpha[pixel[3] as usize] += 1;
+  }
+
+  for colour in visible_pixels(pixels) {
+    histogram.red[colour.red as usize] += 1;
+    histogram.green[colour.green as usize] += 1;
+    histogram.blue[colour.blue as usize] += 1;
+
+    let (hue, saturation, lightness) = HslColour::from(colour).into();
+    // Hue is meaningless for greys, so they are left out of the hue histogram.
+    if saturation > 0.0 {
+      histogram.hue[bin(hue, 360) % 360] += 1;
+    }
+    histogram.saturation[bin(saturation, 101)] += 1;
+    histogram.lightness[bin(lightness, 101)] += 1;
+
+    let (lab_lightness, _, _) = LabColour::from(colour).into();
+    histogram.lab_lightness[bin(lab_lightness, 101)] += 1;
+  }
+
+  histogram
+}
+
+fn mean_and_variance(values: &[f32]) -> (f32, f32) {
+  let count = values.len() as f32;
+  let mean = values.iter().sum::<f32>() / count;
+  let variance = values
+    .iter()
+    .map(|value| (value - mean) * (value - mean))
+    .sum::<f32>()
+    / count;
+  (mean, variance)
+}
+
+fn median(values: &mut [f32]) -> f32 {
+  values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+  let middle = values.len() / 2;
+  if values.len().is_multiple_of(2) {
+    (values[middle - 1] + values[middle]) / 2.0
+  } else {
+    values[middle]
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn statistics(pixels: &[u8]) -> Option<Statistics> {
+  let mut lab_lightness = vec![];
+  let mut luminance = vec![];
+  let mut red_green = vec![];
+  let mut yellow_blue = vec![];
+
+  for colour in visible_pixels(pixels) {
+    let (lightness, _, _) = LabColour::from(colour).into();
+    lab_lightness.push(lightness);
+
+    let (red, green, blue) = colour.to_linear();
+    luminance.push(red * 0.2126 + green * 0.7152 + blue * 0.0722);
+
+    let (red, green, blue) = colour.into();
+    red_green.push(red - green);
+    yellow_blue.push(0.5 * (red + green) - blue);
+  }
+
+  if lab_lightness.is_empty() {
+    return None;
+  }
+
+  let (mean, variance) = mean_and_variance(&lab_lightness);
+  let median = median(&mut lab_lightness);
+
+  // Hasler & Süsstrunk, "Measuring colourfulness in natural images" (2003).
+  let (red_green_mean, red_green_variance) = mean_and_variance(&red_green);
+  let (yellow_blue_mean, yellow_blue_variance) = mean_and_variance(&yellow_blue);
+  let colourfulness = (red_green_variance + yellow_blue_variance).sqrt()
+    + 0.3 * (red_green_mean * red_green_mean + yellow_blue_mean * yellow_blue_mean).sqrt();
+
+  // RMS contrast of relative luminance.
+  let (luminance_mean, luminance_variance) = mean_and_variance(&luminance);
+  let contrast = luminance_variance.sqrt();
+
+  // Where the log-average luminance sits between the darkest and brightest pixels.
+  let log = |value: f32| (value + LOG_DELTA).ln();
+  let log_average = luminance.iter().map(|value| log(*value)).sum::<f32>() / luminance.len() as f32;
+  let log_min = log(luminance.iter().cloned().fold(f32::INFINITY, f32::min));
+  let log_max = log(luminance.iter().cloned().fold(f32::NEG_INFINITY, f32::max));
+  let key = if log_max - log_min > f32::EPSILON {
+    (log_average - log_min) / (log_max - log_min)
+  } else {
+    luminance_mean
+  };
+
+  Some(Statistics {
+    mean,
+    median,
+    variance,
+    colourfulness,
+    contrast,
+    key,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::fixtures::image;
+
+  mod histogram {
+    use super::*;
+
+    #[test]
+    fn channels() {
+      let result = histogram(&image(&[(255, 0, 0, 255), (255, 0, 0, 255), (0, 0, 0, 0)]));
+      assert_eq!(result.red()[255], 2);
+      assert_eq!(result.green()[0], 2);
+      assert_eq!(result.blue()[0], 2);
+      assert_eq!(result.alpha()[255], 2);
+      assert_eq!(result.alpha()[0], 1);
+    }
+
+    #[test]
+    fn hsl() {
+      let result = histogram(&image(&[(102, 51, 153, 255), (128, 128, 128, 255)]));
+      assert_eq!(result.hue()[270], 1);
+      assert_eq!(result.hue().iter().sum::<u32>(), 1);
+      assert_eq!(result.saturation()[50], 1);
+      assert_eq!(result.saturation()[0], 1);
+      assert_eq!(result.lightness()[40], 1);
+      assert_eq!(result.lightness()[50], 1);
+    }
+
+    #[test]
+    fn lab_lightness() {
+      let result = histogram(&image(&[
+        (255, 255, 255, 255),
+        (0, 0, 0, 255),
+        (102, 51, 153, 255),
+      ]));
+      assert_eq!(result.lab_lightness()[100], 1);
+      assert_eq!(result.lab_lightness()[0], 1);
+      assert_eq!(result.lab_lightness()[33], 1);
+    }
+  }
+
+  mod statistics {
+    use super::*;
+
+    #[test]
+    fn empty() {
+      assert_eq!(statistics(&image(&[(255, 0, 0, 0)])), None)
+    }
+
+    #[test]
+    fn black_and_white() {
+      let result = statistics(&image(&[
+        (0, 0, 0, 255),
+        (255, 255, 255, 255),
+        (0, 0, 0, 255),
+        (255, 255, 255, 255),
+      ]))
+      .unwrap();
+      assert_eq!(result.mean, 50.0);
+      assert_eq!(result.median, 50.0);
+      assert_eq!(result.variance, 2500.0);
+      assert_eq!(result.colourfulness, 0.0);
+      assert_eq!(result.contrast, 0.5);
+      assert!((result.key - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn colourful_image_is_more_colourful() {
+      let grey = statistics(&image(&[(120, 120, 120, 255), (140, 140, 140, 255)])).unwrap();
+      let colourful = statistics(&image(&[(240, 20, 20, 255), (20, 20, 240, 255)])).unwrap();
+      assert_eq!(grey.colourfulness, 0.0);
+      assert!(colourful.colourfulness > 100.0);
+    }
+
+    #[test]
+    fn low_key() {
+      let result = statistics(&image(&[
+        (10, 10, 10, 255),
+        (20, 20, 20, 255),
+        (15, 15, 15, 255),
+        (255, 255, 255, 255),
+      ]))
+      .unwrap();
+      assert!(result.key < 0.5);
+    }
+  }
+}
@@ -0,0 +1,382 @@
+use crate::colour::LabColour;
+use crate::colour::RgbColour;
+use crate::colour::XyzColour;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// The same D65 white and lightness curve as `XyzColour::to_lab`, so L* agrees between Lab and Luv.
+const WHITE: (f32, f32, f32) = (0.95047, 1.0, 1.08883);
+const EPSILON: f32 = 0.008856;
+const KAPPA: f32 = 903.3;
+
+// CIE LCh(ab), the cylindrical form of Lab. Hue is in degrees from 0 to 360.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct LchColour {
+  pub lightness: f32,
+  pub chroma: f32,
+  pub hue: f32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct LuvColour {
+  pub lightness: f32,
+  pub u: f32,
+  pub v: f32,
+}
+
+// CIE LCh(uv), the cylindrical form of Luv.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct LchUvColour {
+  pub lightness: f32,
+  pub chroma: f32,
+  pub hue: f32,
+}
+
+// Greys have no hue, so report 0 rather than whatever atan2 makes of rounding noise.
+fn to_polar(a: f32, b: f32) -> (f32, f32) {
+  let chroma = (a * a + b * b).sqrt();
+  let hue = if chroma == 0.0 {
+    0.0
+  } else {
+    b.atan2(a).to_degrees().rem_euclid(360.0)
+  };
+  (chroma, hue)
+}
+
+fn from_polar(chroma: f32, hue: f32) -> (f32, f32) {
+  let hue = hue.to_radians();
+  (chroma * hue.cos(), chroma * hue.sin())
+}
+
+fn chromaticity(x: f32, y: f32, z: f32) -> (f32, f32) {
+  let denominator = x + 15.0 * y + 3.0 * z;
+  if denominator == 0.0 {
+    return (0.0, 0.0);
+  }
+  (4.0 * x / denominator, 9.0 * y / denominator)
+}
+
+// The signed shortest turn from one hue to another, in (-180, 180].
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hue_difference(from: f32, to: f32) -> f32 {
+  let difference = (to - from).rem_euclid(360.0);
+  if difference > 180.0 {
+    difference - 360.0
+  } else {
+    difference
+  }
+}
+
+// The unsigned angle between two hues, from 0 to 180.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn hue_distance(a: f32, b: f32) -> f32 {
+  hue_difference(a, b).abs()
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LchColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(lightness: f32, chroma: f32, hue: f32) -> LchColour {
+    LchColour {
+      lightness,
+      chroma,
+      hue,
+    }
+  }
+
+  pub fn from_lab(lab: &LabColour) -> LchColour {
+    let (lightness, a, b) = (*lab).into();
+    let (chroma, hue) = to_polar(a, b);
+    LchColour {
+      lightness,
+      chroma,
+      hue,
+    }
+  }
+
+  pub fn from_xyz(xyz: &XyzColour) -> LchColour {
+    LchColour::from_lab(&xyz.to_lab())
+  }
+
+  pub fn from_rgb(colour: &RgbColour) -> LchColour {
+    LchColour::from_lab(&colour.to_lab())
+  }
+
+  pub fn to_lab(&self) -> LabColour {
+    let (a, b) = from_polar(self.chroma, self.hue);
+    LabColour::new(self.lightness, a, b)
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    self.to_lab().to_xyz()
+  }
+
+  pub fn to_rgb(&self) -> RgbColour {
+    self.to_lab().to_rgb()
+  }
+
+  // CIE delta H*, the hue part of a colour difference, weighted by chroma so greys count for little.
+  pub fn delta_hue(&self, other: &LchColour) -> f32 {
+    let angle = hue_difference(self.hue, other.hue).to_radians();
+    2.0 * (self.chroma * other.chroma).sqrt() * (angle / 2.0).sin()
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LabColour {
+  pub fn to_lch(&self) -> LchColour {
+    LchColour::from_lab(self)
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LuvColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(lightness: f32, u: f32, v: f32) -> LuvColour {
+    LuvColour { lightness, u, v }
+  }
+
+  pub fn from_xyz(xyz: &XyzColour) -> LuvColour {
+    let y = xyz.y / WHITE.1;
+    let lightness = if y > EPSILON {
+      116.0 * y.cbrt() - 16.0
+    } else {
+      KAPPA * y
+    };
+    if lightness == 0.0 {
+      return LuvColour::new(0.0, 0.0, 0.0);
+    }
+
+    let (u_prime, v_prime) = chromaticity(xyz.x, xyz.y, xyz.z);
+    let (u_white, v_white) = chromaticity(WHITE.0, WHITE.1, WHITE.2);
+
+    LuvColour {
+      lightness,
+      u: 13.0 * lightness * (u_prime - u_white),
+      v: 13.0 * lightness * (v_prime - v_white),
+    }
+  }
+
+  pub fn from_rgb(colour: &RgbColour) -> LuvColour {
+    LuvColour::from_xyz(&colour.to_xyz())
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    if self.lightness <= 0.0 {
+      return XyzColour::new(0.0, 0.0, 0.0);
+    }
+
+    let (u_white, v_white) = chromaticity(WHITE.0, WHITE.1, WHITE.2);
+    let u_prime = self.u / (13.0 * self.lightness) + u_white;
+    let v_prime = self.v / (13.0 * self.lightness) + v_white;
+    let y = if self.lightness > KAPPA * EPSILON {
+      ((self.lightness + 16.0) / 116.0).powi(3)
+    } else {
+      self.lightness / KAPPA
+    } * WHITE.1;
+    if v_prime == 0.0 {
+      return XyzColour::new(0.0, y, 0.0);
+    }
+
+    XyzColour {
+      x: y * 9.0 * u_prime / (4.0 * v_prime),
+      y,
+      z: y * (12.0 - 3.0 * u_prime - 20.0 * v_prime) / (4.0 * v_prime),
+    }
+  }
+
+  pub fn to_rgb(&self) -> RgbColour {
+    self.to_xyz().to_rgb()
+  }
+
+  pub fn to_lab(&self) -> LabColour {
+    self.to_xyz().to_lab()
+  }
+
+  pub fn to_lch(&self) -> LchUvColour {
+    let (chroma, hue) = to_polar(self.u, self.v);
+    LchUvColour {
+      lightness: self.lightness,
+      chroma,
+      hue,
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl XyzColour {
+  pub fn to_luv(&self) -> LuvColour {
+    LuvColour::from_xyz(self)
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl LchUvColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(lightness: f32, chroma: f32, hue: f32) -> LchUvColour {
+    LchUvColour {
+      lightness,
+      chroma,
+      hue,
+    }
+  }
+
+  pub fn from_xyz(xyz: &XyzColour) -> LchUvColour {
+    LuvColour::from_xyz(xyz).to_lch()
+  }
+
+  pub fn from_rgb(colour: &RgbColour) -> LchUvColour {
+    LuvColour::from_rgb(colour).to_lch()
+  }
+
+  pub fn to_luv(&self) -> LuvColour {
+    let (u, v) = from_polar(self.chroma, self.hue);
+    LuvColour::new(self.lightness, u, v)
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    self.to_luv().to_xyz()
+  }
+
+  pub fn to_rgb(&self) -> RgbColour {
+    self.to_luv().to_rgb()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_close(actual: (f32, f32, f32), expected: (f32, f32, f32)) {
+    assert!(
+      (actual.0 - expected.0).abs() < 0.05
+        && (actual.1 - expected.1).abs() < 0.05
+        && (actual.2 - expected.2).abs() < 0.05,
+      "{:?} != {:?}",
+      actual,
+      expected
+    );
+  }
+
+  mod hue_difference {
+    use super::*;
+
+    #[test]
+    fn wraps_around() {
+      assert_eq!(hue_difference(350.0, 10.0), 20.0);
+      assert_eq!(hue_difference(10.0, 350.0), -20.0);
+      assert_eq!(hue_difference(0.0, 180.0), 180.0);
+      assert_eq!(hue_difference(180.0, 0.0), 180.0);
+      assert_eq!(hue_difference(-30.0, 750.0), 60.0);
+      assert_eq!(hue_distance(10.0, 350.0), 20.0);
+    }
+  }
+
+  mod lch {
+    use super::*;
+
+    #[test]
+    fn from_rgb() {
+      let colour = LchColour::from_rgb(&RgbColour::from_tuple(255, 0, 0));
+      assert_close(
+        (colour.lightness, colour.chroma, colour.hue),
+        (53.24, 104.55, 40.0),
+      );
+    }
+
+    #[test]
+    fn grey_has_no_hue() {
+      let colour = RgbColour::from_tuple(128, 128, 128).to_lab().to_lch();
+      assert!(colour.chroma < 0.01);
+      assert_eq!(
+        LchColour::from_lab(&LabColour::new(50.0, 0.0, 0.0)).hue,
+        0.0
+      );
+    }
+
+    #[test]
+    fn round_trip() {
+      let colour = RgbColour::from_tuple(102, 51, 153);
+      assert_eq!(LchColour::from_rgb(&colour).to_rgb(), colour);
+      assert_close(
+        LchColour::new(60.0, 40.0, 300.0)
+          .to_lab()
+          .to_lch()
+          .to_lab()
+          .into(),
+        LchColour::new(60.0, 40.0, 300.0).to_lab().into(),
+      );
+    }
+
+    #[test]
+    fn delta_hue() {
+      let a = LchColour::new(50.0, 20.0, 350.0);
+      let b = LchColour::new(50.0, 20.0, 10.0);
+      assert!((a.delta_hue(&b) - 6.946).abs() < 0.001);
+      assert!((b.delta_hue(&a) + 6.946).abs() < 0.001);
+      assert_eq!(LchColour::new(50.0, 0.0, 0.0).delta_hue(&b), 0.0);
+    }
+  }
+
+  mod luv {
+    use super::*;
+
+    #[test]
+    fn from_rgb() {
+      let colour = LuvColour::from_rgb(&RgbColour::from_tuple(255, 0, 0));
+      assert_close(
+        (colour.lightness, colour.u, colour.v),
+        (53.24, 175.01, 37.76),
+      );
+
+      let white = LuvColour::from_rgb(&RgbColour::from_tuple(255, 255, 255));
+      assert_close((white.lightness, white.u, white.v), (100.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn lightness_matches_lab() {
+      for level in [0, 5, 60, 200] {
+        let colour = RgbColour::from_tuple(level, level, level);
+        let lab: (f32, f32, f32) = colour.to_lab().into();
+        assert!((LuvColour::from_rgb(&colour).lightness - lab.0).abs() < 0.01);
+      }
+    }
+
+    #[test]
+    fn black() {
+      let black = RgbColour::from_tuple(0, 0, 0);
+      assert_eq!(LuvColour::from_rgb(&black), LuvColour::new(0.0, 0.0, 0.0));
+      assert_eq!(LuvColour::new(0.0, 0.0, 0.0).to_rgb(), black);
+    }
+
+    #[test]
+    fn round_trip() {
+      for colour in [
+        RgbColour::from_tuple(102, 51, 153),
+        RgbColour::from_tuple(3, 2, 1),
+        RgbColour::from_tuple(0, 255, 0),
+      ] {
+        assert_eq!(LuvColour::from_rgb(&colour).to_rgb(), colour);
+        assert_eq!(LchUvColour::from_rgb(&colour).to_rgb(), colour);
+      }
+    }
+  }
+
+  mod lch_uv {
+    use super::*;
+
+    #[test]
+    fn from_rgb() {
+      let colour = LchUvColour::from_rgb(&RgbColour::from_tuple(255, 0, 0));
+      assert_close(
+        (colour.lightness, colour.chroma, colour.hue),
+        (53.24, 179.04, 12.17),
+      );
+    }
+  }
+}
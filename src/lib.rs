@@ -1,6 +1,58 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod batch;
+#[cfg(feature = "alloc")]
+pub mod categorical;
 pub mod colour;
+#[cfg(feature = "alloc")]
+pub mod colour_space;
 pub mod comparison;
+pub mod contrast;
+#[cfg(feature = "alloc")]
+pub mod difference;
+#[cfg(feature = "alloc")]
+pub mod dithering;
+#[cfg(feature = "alloc")]
+pub mod extraction;
+#[cfg(feature = "alloc")]
+pub mod filters;
+pub mod float;
+#[cfg(feature = "alloc")]
+pub mod format;
+#[cfg(feature = "alloc")]
+pub mod gamut;
+pub mod hash;
+#[cfg(feature = "alloc")]
+pub mod histogram;
+pub mod lch;
+pub mod oklab;
+#[cfg(feature = "alloc")]
+pub mod palette;
+#[cfg(feature = "serde")]
+pub mod serialization;
+pub mod simulation;
+pub mod temperature;
+#[cfg(feature = "alloc")]
+pub mod terminal;
+#[cfg(feature = "alloc")]
+pub mod tokens;
+#[cfg(feature = "alloc")]
+mod names;
+#[cfg(feature = "alloc")]
 pub mod error_code;
+#[cfg(all(test, feature = "alloc"))]
+mod fixtures;
+#[cfg(not(feature = "std"))]
+mod math;
+mod matrix;
+#[cfg(feature = "alloc")]
+mod prelude;
+#[cfg(feature = "alloc")]
+mod simd;
 mod utils;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
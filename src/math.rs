@@ -0,0 +1,83 @@
+// Float methods that only exist in std, backed by libm for no_std builds.
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) trait Float: Sized {
+  fn round(self) -> Self;
+  fn floor(self) -> Self;
+  fn ceil(self) -> Self;
+  fn rem_euclid(self, rhs: Self) -> Self;
+  fn sqrt(self) -> Self;
+  fn cbrt(self) -> Self;
+  fn powf(self, n: Self) -> Self;
+  fn powi(self, n: i32) -> Self;
+  fn exp(self) -> Self;
+  fn ln(self) -> Self;
+  fn sin(self) -> Self;
+  fn cos(self) -> Self;
+  fn atan2(self, other: Self) -> Self;
+}
+
+macro_rules! impl_float {
+  ($type:ty, $abs:ident, $round:ident, $floor:ident, $ceil:ident, $sqrt:ident, $cbrt:ident, $pow:ident, $exp:ident, $log:ident, $sin:ident, $cos:ident, $atan2:ident) => {
+    impl Float for $type {
+      fn round(self) -> Self {
+        libm::$round(self)
+      }
+
+      fn floor(self) -> Self {
+        libm::$floor(self)
+      }
+
+      fn ceil(self) -> Self {
+        libm::$ceil(self)
+      }
+
+      fn rem_euclid(self, rhs: Self) -> Self {
+        let remainder = self % rhs;
+        if remainder < 0.0 {
+          remainder + libm::$abs(rhs)
+        } else {
+          remainder
+        }
+      }
+
+      fn sqrt(self) -> Self {
+        libm::$sqrt(self)
+      }
+
+      fn cbrt(self) -> Self {
+        libm::$cbrt(self)
+      }
+
+      fn powf(self, n: Self) -> Self {
+        libm::$pow(self, n)
+      }
+
+      fn powi(self, n: i32) -> Self {
+        libm::$pow(self, n as $type)
+      }
+
+      fn exp(self) -> Self {
+        libm::$exp(self)
+      }
+
+      fn ln(self) -> Self {
+        libm::$log(self)
+      }
+
+      fn sin(self) -> Self {
+        libm::$sin(self)
+      }
+
+      fn cos(self) -> Self {
+        libm::$cos(self)
+      }
+
+      fn atan2(self, other: Self) -> Self {
+        libm::$atan2(self, other)
+      }
+    }
+  };
+}
+
+impl_float!(f32, fabsf, roundf, floorf, ceilf, sqrtf, cbrtf, powf, expf, logf, sinf, cosf, atan2f);
+impl_float!(f64, fabs, round, floor, ceil, sqrt, cbrt, pow, exp, log, sin, cos, atan2);
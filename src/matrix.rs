@@ -0,0 +1,60 @@
+use core::ops::{Add, Mul};
+
+pub(crate) type Matrix = [[f32; 3]; 3];
+pub(crate) type Matrix64 = [[f64; 3]; 3];
+
+#[cfg_attr(not(feature = "alloc"), allow(dead_code))]
+pub(crate) fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+  let mut result = [[0.0; 3]; 3];
+  for (row, result_row) in result.iter_mut().enumerate() {
+    for (column, cell) in result_row.iter_mut().enumerate() {
+      *cell = (0..3).map(|index| a[row][index] * b[index][column]).sum();
+    }
+  }
+  result
+}
+
+pub(crate) fn apply<T>(matrix: &[[T; 3]; 3], vector: (T, T, T)) -> (T, T, T)
+where
+  T: Copy + Add<Output = T> + Mul<Output = T>,
+{
+  let row = |row: &[T; 3]| row[0] * vector.0 + row[1] * vector.1 + row[2] * vector.2;
+  (row(&matrix[0]), row(&matrix[1]), row(&matrix[2]))
+}
+
+// Rounds an f64 matrix to f32, so both precisions share one definition.
+pub(crate) const fn narrow(matrix: &Matrix64) -> Matrix {
+  let mut result = [[0.0; 3]; 3];
+  let mut row = 0;
+  while row < 3 {
+    let mut column = 0;
+    while column < 3 {
+      result[row][column] = matrix[row][column] as f32;
+      column += 1;
+    }
+    row += 1;
+  }
+  result
+}
+
+pub(crate) const fn invert(matrix: &Matrix) -> Matrix {
+  let [[a, b, c], [d, e, f], [g, h, i]] = *matrix;
+  let determinant = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+  [
+    [
+      (e * i - f * h) / determinant,
+      (c * h - b * i) / determinant,
+      (b * f - c * e) / determinant,
+    ],
+    [
+      (f * g - d * i) / determinant,
+      (a * i - c * g) / determinant,
+      (c * d - a * f) / determinant,
+    ],
+    [
+      (d * h - e * g) / determinant,
+      (b * g - a * h) / determinant,
+      (a * e - b * d) / determinant,
+    ],
+  ]
+}
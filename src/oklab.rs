@@ -0,0 +1,182 @@
+use crate::colour::RgbColour;
+use crate::colour::XyzColour;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::matrix::apply;
+use crate::matrix::invert;
+use crate::matrix::Matrix;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Björn Ottosson's OKLab matrices, starting from D65-relative XYZ.
+pub(crate) const XYZ_TO_LMS: Matrix = [
+  [0.818_933, 0.361_866_74, -0.128_859_71],
+  [0.032_984_544, 0.929_311_9, 0.036_145_64],
+  [0.048_200_3, 0.264_366_27, 0.633_851_7],
+];
+
+pub(crate) const LMS_TO_OKLAB: Matrix = [
+  [0.210_454_26, 0.793_617_8, -0.004_072_047],
+  [1.977_998_5, -2.428_592_2, 0.450_593_7],
+  [0.025_904_037, 0.782_771_77, -0.808_675_77],
+];
+
+pub(crate) const OKLAB_TO_LMS: Matrix = invert(&LMS_TO_OKLAB);
+pub(crate) const LMS_TO_XYZ: Matrix = invert(&XYZ_TO_LMS);
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct OklabColour {
+  pub lightness: f32,
+  pub a: f32,
+  pub b: f32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct OklchColour {
+  pub lightness: f32,
+  pub chroma: f32,
+  pub hue: f32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl OklabColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(lightness: f32, a: f32, b: f32) -> OklabColour {
+    OklabColour { lightness, a, b }
+  }
+
+  pub fn from_xyz(xyz: &XyzColour) -> OklabColour {
+    let (l, m, s) = apply(&XYZ_TO_LMS, (xyz.x, xyz.y, xyz.z));
+    let (lightness, a, b) = apply(&LMS_TO_OKLAB, (l.cbrt(), m.cbrt(), s.cbrt()));
+    OklabColour { lightness, a, b }
+  }
+
+  pub fn from_rgb(colour: &RgbColour) -> OklabColour {
+    OklabColour::from_xyz(&colour.to_xyz())
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    let (l, m, s) = apply(&OKLAB_TO_LMS, (self.lightness, self.a, self.b));
+    let (x, y, z) = apply(&LMS_TO_XYZ, (l * l * l, m * m * m, s * s * s));
+    XyzColour::new(x, y, z)
+  }
+
+  pub fn to_lch(&self) -> OklchColour {
+    let chroma = (self.a * self.a + self.b * self.b).sqrt();
+    let hue = if chroma == 0.0 {
+      0.0
+    } else {
+      self.b.atan2(self.a).to_degrees().rem_euclid(360.0)
+    };
+
+    OklchColour {
+      lightness: self.lightness,
+      chroma,
+      hue,
+    }
+  }
+
+  // Euclidean distance in OKLab, known as deltaEOK.
+  pub fn delta_e(&self, other: &OklabColour) -> f32 {
+    ((self.lightness - other.lightness).powi(2)
+      + (self.a - other.a).powi(2)
+      + (self.b - other.b).powi(2))
+    .sqrt()
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl OklchColour {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(lightness: f32, chroma: f32, hue: f32) -> OklchColour {
+    OklchColour {
+      lightness,
+      chroma,
+      hue,
+    }
+  }
+
+  pub fn from_rgb(colour: &RgbColour) -> OklchColour {
+    OklabColour::from_rgb(colour).to_lch()
+  }
+
+  pub fn to_oklab(&self) -> OklabColour {
+    let hue = self.hue.to_radians();
+    OklabColour {
+      lightness: self.lightness,
+      a: self.chroma * libm::cosf(hue),
+      b: self.chroma * libm::sinf(hue),
+    }
+  }
+
+  pub fn to_xyz(&self) -> XyzColour {
+    self.to_oklab().to_xyz()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_oklab(actual: OklabColour, expected: (f32, f32, f32)) {
+    assert!(
+      (actual.lightness - expected.0).abs() < 0.0005
+        && (actual.a - expected.1).abs() < 0.0005
+        && (actual.b - expected.2).abs() < 0.0005,
+      "{:?} != {:?}",
+      actual,
+      expected
+    );
+  }
+
+  mod oklab {
+    use super::*;
+
+    #[test]
+    fn white() {
+      assert_oklab(
+        OklabColour::from_rgb(&RgbColour::from_tuple(255, 255, 255)),
+        (1.0, 0.0, 0.0),
+      );
+    }
+
+    #[test]
+    fn red() {
+      assert_oklab(
+        OklabColour::from_rgb(&RgbColour::from_tuple(255, 0, 0)),
+        (0.62796, 0.22486, 0.12585),
+      );
+    }
+
+    #[test]
+    fn round_trip() {
+      let colour = RgbColour::from_tuple(102, 51, 153);
+      assert_eq!(OklabColour::from_rgb(&colour).to_xyz().to_rgb(), colour);
+    }
+  }
+
+  mod oklch {
+    use super::*;
+
+    #[test]
+    fn from_rgb() {
+      let colour = OklchColour::from_rgb(&RgbColour::from_tuple(255, 0, 0));
+      assert!((colour.chroma - 0.25768).abs() < 0.0005);
+      assert!((colour.hue - 29.234).abs() < 0.05);
+    }
+
+    #[test]
+    fn grey_has_no_hue() {
+      let colour = OklchColour::from_rgb(&RgbColour::from_tuple(128, 128, 128));
+      assert!(colour.chroma < 0.0001);
+    }
+
+    #[test]
+    fn round_trip() {
+      let colour = OklabColour::new(0.5, 0.1, -0.05);
+      assert_oklab(colour.to_lch().to_oklab(), (0.5, 0.1, -0.05));
+    }
+  }
+}
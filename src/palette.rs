@@ -0,0 +1,1253 @@
+use crate::colour::HslColour;
+use crate::colour::LabColour;
+use crate::colour::RgbColour;
+use crate::comparison::Metric;
+use crate::error_code::ErrorCode;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+use crate::oklab::OklabColour;
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const ASE_GROUP_START: u16 = 0xc001;
+const ASE_GROUP_END: u16 = 0xc002;
+const ASE_COLOUR: u16 = 0x0001;
+const ASE_NORMAL: u16 = 2;
+
+const ACO_RGB: u16 = 0;
+const ACO_HSB: u16 = 1;
+const ACO_CMYK: u16 = 2;
+const ACO_LAB: u16 = 7;
+const ACO_GREY: u16 = 8;
+
+// Grid resolution per Lab axis for Hilbert ordering.
+const HILBERT_BITS: u32 = 8;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PaletteOrder {
+  Hue,
+  Lightness,
+  Luminance,
+  Hilbert,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct ClosePair {
+  pub a: usize,
+  pub b: usize,
+  pub distance: f32,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum PaletteFormat {
+  Gpl,
+  Ase,
+  Aco,
+  PaintNet,
+  JascPal,
+}
+
+impl PaletteFormat {
+  fn name(self) -> &'static str {
+    match self {
+      PaletteFormat::Gpl => "GPL",
+      PaletteFormat::Ase => "ASE",
+      PaletteFormat::Aco => "ACO",
+      PaletteFormat::PaintNet => "Paint.NET",
+      PaletteFormat::JascPal => "JASC-PAL",
+    }
+  }
+}
+
+// Lab swatches are kept as Lab so that colours outside sRGB survive a round trip.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum SwatchColour {
+  Rgb(RgbColour),
+  Lab(LabColour),
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Swatch {
+  name: String,
+  group: Option<String>,
+  colour: SwatchColour,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Swatch {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new(name: &str, colour: &RgbColour) -> Swatch {
+    Swatch {
+      name: name.to_string(),
+      group: None,
+      colour: SwatchColour::Rgb(*colour),
+    }
+  }
+
+  pub fn from_lab(name: &str, colour: &LabColour) -> Swatch {
+    Swatch {
+      name: name.to_string(),
+      group: None,
+      colour: SwatchColour::Lab(*colour),
+    }
+  }
+
+  pub fn with_group(&self, group: &str) -> Swatch {
+    Swatch {
+      group: Some(group.to_string()),
+      ..self.clone()
+    }
+  }
+
+  pub fn name(&self) -> String {
+    self.name.clone()
+  }
+
+  pub fn group(&self) -> Option<String> {
+    self.group.clone()
+  }
+
+  pub fn is_lab(&self) -> bool {
+    matches!(self.colour, SwatchColour::Lab(_))
+  }
+
+  pub fn to_rgb(&self) -> RgbColour {
+    match self.colour {
+      SwatchColour::Rgb(colour) => colour,
+      SwatchColour::Lab(colour) => colour.to_rgb(),
+    }
+  }
+
+  pub fn to_lab(&self) -> LabColour {
+    match self.colour {
+      SwatchColour::Rgb(colour) => colour.to_lab(),
+      SwatchColour::Lab(colour) => colour,
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Palette {
+  name: Option<String>,
+  swatches: Vec<Swatch>,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Palette {
+  #[cfg_attr(feature = "wasm", wasm_bindgen(constructor))]
+  pub fn new() -> Palette {
+    Palette::default()
+  }
+
+  pub fn from_colours(colours: Vec<RgbColour>) -> Palette {
+    Palette {
+      name: None,
+      swatches: colours
+        .iter()
+        .map(|colour| Swatch::new("", colour))
+        .collect(),
+    }
+  }
+
+  pub fn name(&self) -> Option<String> {
+    self.name.clone()
+  }
+
+  pub fn set_name(&mut self, name: &str) {
+    self.name = Some(name.to_string());
+  }
+
+  pub fn len(&self) -> usize {
+    self.swatches.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.swatches.is_empty()
+  }
+
+  pub fn get(&self, index: usize) -> Option<Swatch> {
+    self.swatches.get(index).cloned()
+  }
+
+  pub fn push(&mut self, swatch: &Swatch) {
+    self.swatches.push(swatch.clone());
+  }
+
+  pub fn colours(&self) -> Vec<RgbColour> {
+    self.swatches.iter().map(Swatch::to_rgb).collect()
+  }
+
+  pub fn read(bytes: &[u8], format: PaletteFormat) -> Result<Palette, ErrorCode> {
+    match format {
+      PaletteFormat::Gpl => read_gpl(bytes),
+      PaletteFormat::Ase => read_ase(bytes),
+      PaletteFormat::Aco => read_aco(bytes),
+      PaletteFormat::PaintNet => read_paint_net(bytes),
+      PaletteFormat::JascPal => read_jasc_pal(bytes),
+    }
+  }
+
+  // Picks the format from the file's signature.
+  pub fn parse(bytes: &[u8]) -> Result<Palette, ErrorCode> {
+    match detect_palette_format(bytes) {
+      Some(format) => Palette::read(bytes, format),
+      None => Err(ErrorCode::InvalidPalette {
+        format: "palette".to_string(),
+        position: 0,
+        expected: "a GPL, ASE, ACO, Paint.NET or JASC-PAL signature".to_string(),
+      }),
+    }
+  }
+
+  // Formats without names or groups drop them; Lab swatches are written as RGB unless the format supports Lab.
+  // ACO keeps the first 65535 swatches and ASE cuts names to 65534 UTF-16 units, the limits of their u16 fields.
+  pub fn write(&self, format: PaletteFormat) -> Vec<u8> {
+    match format {
+      PaletteFormat::Gpl => write_gpl(self),
+      PaletteFormat::Ase => write_ase(self),
+      PaletteFormat::Aco => write_aco(self),
+      PaletteFormat::PaintNet => write_paint_net(self),
+      PaletteFormat::JascPal => write_jasc_pal(self),
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Palette {
+  pub fn sort(&mut self, order: PaletteOrder) {
+    match order {
+      PaletteOrder::Hue => {
+        // Greys have no hue, so they lead in order of lightness.
+        let key = |swatch: &Swatch| {
+          let (hue, saturation, lightness) = HslColour::from(swatch.to_rgb()).into();
+          (saturation > 0.0, hue, lightness)
+        };
+        self.swatches.sort_by(|a, b| {
+          let (a, b) = (key(a), key(b));
+          a.0
+            .cmp(&b.0)
+            .then(a.1.total_cmp(&b.1))
+            .then(a.2.total_cmp(&b.2))
+        });
+      }
+      PaletteOrder::Lightness => {
+        let lightness = |swatch: &Swatch| <(f32, f32, f32)>::from(swatch.to_lab()).0;
+        self
+          .swatches
+          .sort_by(|a, b| lightness(a).total_cmp(&lightness(b)))
+      }
+      PaletteOrder::Luminance => self.swatches.sort_by(|a, b| {
+        a.to_rgb()
+          .relative_luminance()
+          .total_cmp(&b.to_rgb().relative_luminance())
+      }),
+      PaletteOrder::Hilbert => self.swatches.sort_by_cached_key(hilbert_index),
+    }
+  }
+
+  // Drops any swatch within `tolerance` of an earlier one.
+  pub fn dedupe(&mut self, tolerance: f32, metric: Metric) {
+    let mut kept: Vec<(f32, f32, f32)> = vec![];
+    self.swatches.retain(|swatch| {
+      let point = coordinates(swatch, metric);
+      if kept
+        .iter()
+        .any(|other| metric.distance(&point, other) <= tolerance)
+      {
+        false
+      } else {
+        kept.push(point);
+        true
+      }
+    });
+  }
+
+  pub fn min_distance(&self, metric: Metric) -> Option<f32> {
+    self
+      .pairs(metric)
+      .iter()
+      .map(|pair| pair.distance)
+      .fold(None, |min: Option<f32>, distance| {
+        Some(min.map_or(distance, |min| min.min(distance)))
+      })
+  }
+
+  // Pairs closer than `threshold`, closest first.
+  pub fn close_pairs(&self, threshold: f32, metric: Metric) -> Vec<ClosePair> {
+    let mut pairs: Vec<ClosePair> = self
+      .pairs(metric)
+      .into_iter()
+      .filter(|pair| pair.distance < threshold)
+      .collect();
+    pairs.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    pairs
+  }
+
+  // Row-major `len() * len()` matrix of distances between every pair of swatches.
+  pub fn distance_matrix(&self, metric: Metric) -> Vec<f32> {
+    let points = self.points(metric);
+    points
+      .iter()
+      .flat_map(|a| points.iter().map(move |b| metric.distance(a, b)))
+      .collect()
+  }
+}
+
+impl Palette {
+  pub(crate) fn swatches(&self) -> &[Swatch] {
+    &self.swatches
+  }
+
+  fn points(&self, metric: Metric) -> Vec<(f32, f32, f32)> {
+    self
+      .swatches
+      .iter()
+      .map(|swatch| coordinates(swatch, metric))
+      .collect()
+  }
+
+  fn pairs(&self, metric: Metric) -> Vec<ClosePair> {
+    let points = self.points(metric);
+    let mut pairs = vec![];
+    for a in 0..points.len() {
+      for b in a + 1..points.len() {
+        pairs.push(ClosePair {
+          a,
+          b,
+          distance: metric.distance(&points[a], &points[b]),
+        });
+      }
+    }
+    pairs
+  }
+}
+
+// Lab swatches are measured in Lab directly rather than through their sRGB approximation.
+fn coordinates(swatch: &Swatch, metric: Metric) -> (f32, f32, f32) {
+  match (metric, swatch.colour) {
+    (Metric::Lab | Metric::Ciede2000, SwatchColour::Lab(colour)) => colour.into(),
+    (Metric::Oklab, SwatchColour::Lab(colour)) => {
+      let oklab = OklabColour::from_xyz(&colour.to_xyz());
+      (oklab.lightness, oklab.a, oklab.b)
+    }
+    _ => metric.coordinates(swatch.to_rgb()),
+  }
+}
+
+// Position along a 3D Hilbert curve through Lab, so neighbours in the order are neighbours in colour.
+fn hilbert_index(swatch: &Swatch) -> u64 {
+  let (lightness, a, b) = swatch.to_lab().into();
+  let max = ((1 << HILBERT_BITS) - 1) as f32;
+  let scale = |value: f32, min: f32, max_value: f32| {
+    (((value - min) / (max_value - min)).clamp(0.0, 1.0) * max).round() as u32
+  };
+  let mut x = [
+    scale(lightness, 0.0, 100.0),
+    scale(a, -128.0, 127.0),
+    scale(b, -128.0, 127.0),
+  ];
+
+  // Skilling's axes-to-transpose transform.
+  let mut q = 1 << (HILBERT_BITS - 1);
+  while q > 1 {
+    let p = q - 1;
+    for i in 0..3 {
+      if x[i] & q != 0 {
+        x[0] ^= p;
+      } else {
+        let t = (x[0] ^ x[i]) & p;
+        x[0] ^= t;
+        x[i] ^= t;
+      }
+    }
+    q >>= 1;
+  }
+  for i in 1..3 {
+    x[i] ^= x[i - 1];
+  }
+  let mut t = 0;
+  q = 1 << (HILBERT_BITS - 1);
+  while q > 1 {
+    if x[2] & q != 0 {
+      t ^= q - 1;
+    }
+    q >>= 1;
+  }
+  for value in x.iter_mut() {
+    *value ^= t;
+  }
+
+  let mut index = 0u64;
+  for bit in (0..HILBERT_BITS).rev() {
+    for value in x {
+      index = (index << 1) | ((value >> bit) & 1) as u64;
+    }
+  }
+  index
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn detect_palette_format(bytes: &[u8]) -> Option<PaletteFormat> {
+  if bytes.starts_with(b"ASEF") {
+    Some(PaletteFormat::Ase)
+  } else if bytes.starts_with(b"GIMP Palette") {
+    Some(PaletteFormat::Gpl)
+  } else if bytes.starts_with(b"JASC-PAL") {
+    Some(PaletteFormat::JascPal)
+  } else if bytes.starts_with(&[0, 1]) || bytes.starts_with(&[0, 2]) {
+    Some(PaletteFormat::Aco)
+  } else if bytes.starts_with(b";")
+    || (bytes.len() >= 8 && bytes[..8].iter().all(u8::is_ascii_hexdigit))
+  {
+    Some(PaletteFormat::PaintNet)
+  } else {
+    None
+  }
+}
+
+fn error(format: PaletteFormat, position: usize, expected: &str) -> ErrorCode {
+  ErrorCode::InvalidPalette {
+    format: format.name().to_string(),
+    position,
+    expected: expected.to_string(),
+  }
+}
+
+fn text(bytes: &[u8], format: PaletteFormat) -> Result<&str, ErrorCode> {
+  core::str::from_utf8(bytes).map_err(|utf8| error(format, utf8.valid_up_to(), "UTF-8 text"))
+}
+
+// Lines with the byte offset they start at, without their line ending.
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+  text.split('\n').scan(0, |offset, line| {
+    let start = *offset;
+    *offset += line.len() + 1;
+    Some((start, line.trim_end_matches('\r')))
+  })
+}
+
+fn channel(value: f32) -> u8 {
+  (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn read_gpl(bytes: &[u8]) -> Result<Palette, ErrorCode> {
+  let format = PaletteFormat::Gpl;
+  let mut lines = lines(text(bytes, format)?);
+  match lines.next() {
+    Some((_, header)) if header.trim() == "GIMP Palette" => {}
+    _ => return Err(error(format, 0, "GIMP Palette header")),
+  }
+
+  let mut palette = Palette::new();
+  for (offset, line) in lines {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("Columns:") {
+      continue;
+    }
+    if let Some(name) = trimmed.strip_prefix("Name:") {
+      palette.set_name(name.trim());
+      continue;
+    }
+
+    let mut rest = trimmed;
+    let mut channels = [0; 3];
+    for channel in channels.iter_mut() {
+      let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+      let position = offset + line.len() - line.trim_start().len() + (trimmed.len() - rest.len());
+      *channel = rest[..end]
+        .parse()
+        .map_err(|_| error(format, position, "a channel from 0 to 255"))?;
+      rest = rest[end..].trim_start();
+    }
+    palette.push(&Swatch::new(
+      rest,
+      &RgbColour::from_tuple(channels[0], channels[1], channels[2]),
+    ));
+  }
+  Ok(palette)
+}
+
+fn write_gpl(palette: &Palette) -> Vec<u8> {
+  let mut output = String::from("GIMP Palette\n");
+  if let Some(name) = &palette.name {
+    output += &format!("Name: {}\n", name);
+  }
+  output += "#\n";
+  for swatch in &palette.swatches {
+    let colour = swatch.to_rgb();
+    let name = if swatch.name.is_empty() {
+      colour.to_hex()
+    } else {
+      swatch.name.clone()
+    };
+    output += &format!(
+      "{:3} {:3} {:3}\t{}\n",
+      colour.red, colour.green, colour.blue, name
+    );
+  }
+  output.into_bytes()
+}
+
+fn read_jasc_pal(bytes: &[u8]) -> Result<Palette, ErrorCode> {
+  let format = PaletteFormat::JascPal;
+  let mut lines = lines(text(bytes, format)?);
+  let mut header = |expected: &str| {
+    lines
+      .next()
+      .filter(|(_, line)| line.trim() == expected)
+      .ok_or_else(|| error(format, 0, expected))
+  };
+  header("JASC-PAL")?;
+  header("0100")?;
+
+  let (offset, count) = lines
+    .next()
+    .ok_or_else(|| error(format, bytes.len(), "a colour count"))?;
+  let count: usize = count
+    .trim()
+    .parse()
+    .map_err(|_| error(format, offset, "a colour count"))?;
+
+  let mut palette = Palette::new();
+  for _ in 0..count {
+    let (offset, line) = lines
+      .next()
+      .ok_or_else(|| error(format, bytes.len(), "a colour"))?;
+    let channels = line
+      .split_whitespace()
+      .map(str::parse)
+      .collect::<Result<Vec<u8>, _>>()
+      .ok()
+      .filter(|channels| channels.len() == 3)
+      .ok_or_else(|| error(format, offset, "three channels from 0 to 255"))?;
+    palette.push(&Swatch::new(
+      "",
+      &RgbColour::from_tuple(channels[0], channels[1], channels[2]),
+    ));
+  }
+  Ok(palette)
+}
+
+fn write_jasc_pal(palette: &Palette) -> Vec<u8> {
+  let mut output = format!("JASC-PAL\r\n0100\r\n{}\r\n", palette.len());
+  for colour in palette.colours() {
+    output += &format!("{} {} {}\r\n", colour.red, colour.green, colour.blue);
+  }
+  output.into_bytes()
+}
+
+fn read_paint_net(bytes: &[u8]) -> Result<Palette, ErrorCode> {
+  let format = PaletteFormat::PaintNet;
+  let mut palette = Palette::new();
+  for (offset, line) in lines(text(bytes, format)?) {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with(';') {
+      continue;
+    }
+
+    // Entries are AARRGGBB; alpha is dropped.
+    let hex = match line.len() {
+      8 => line.get(2..),
+      _ => Some(line),
+    };
+    let colour = hex
+      .and_then(|hex| RgbColour::from_hex(hex).ok())
+      .ok_or_else(|| error(format, offset, "an AARRGGBB hex colour"))?;
+    palette.push(&Swatch::new("", &colour));
+  }
+  Ok(palette)
+}
+
+fn write_paint_net(palette: &Palette) -> Vec<u8> {
+  let mut output = String::from(
+    "; paint.net Palette File\r\n; Lines that start with a semicolon are comments\r\n",
+  );
+  if let Some(name) = &palette.name {
+    output += &format!("; {}\r\n", name);
+  }
+  for colour in palette.colours() {
+    output += &format!("FF{}\r\n", colour.to_hex().to_uppercase());
+  }
+  output.into_bytes()
+}
+
+struct Reader<'a> {
+  bytes: &'a [u8],
+  position: usize,
+  format: PaletteFormat,
+}
+
+impl<'a> Reader<'a> {
+  fn new(bytes: &'a [u8], format: PaletteFormat) -> Reader<'a> {
+    Reader {
+      bytes,
+      position: 0,
+      format,
+    }
+  }
+
+  fn remaining(&self) -> usize {
+    self.bytes.len() - self.position
+  }
+
+  fn take(&mut self, length: usize, expected: &str) -> Result<&'a [u8], ErrorCode> {
+    if self.remaining() < length {
+      return Err(error(self.format, self.position, expected));
+    }
+    let bytes = &self.bytes[self.position..self.position + length];
+    self.position += length;
+    Ok(bytes)
+  }
+
+  fn u16(&mut self, expected: &str) -> Result<u16, ErrorCode> {
+    let bytes = self.take(2, expected)?;
+    Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+  }
+
+  fn u32(&mut self, expected: &str) -> Result<u32, ErrorCode> {
+    let bytes = self.take(4, expected)?;
+    Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+  }
+
+  fn f32(&mut self, expected: &str) -> Result<f32, ErrorCode> {
+    self.u32(expected).map(f32::from_bits)
+  }
+
+  // UTF-16BE text of `length` code units, which usually includes a trailing null.
+  fn utf16(&mut self, length: usize) -> Result<String, ErrorCode> {
+    let position = self.position;
+    let bytes = length
+      .checked_mul(2)
+      .ok_or_else(|| error(self.format, position, "a UTF-16 name"))?;
+    let units = self
+      .take(bytes, "a UTF-16 name")?
+      .chunks_exact(2)
+      .map(|unit| u16::from_be_bytes([unit[0], unit[1]]))
+      .collect::<Vec<_>>();
+    String::from_utf16(&units)
+      .map(|name| name.trim_end_matches('\0').to_string())
+      .map_err(|_| error(self.format, position, "a UTF-16 name"))
+  }
+}
+
+fn push_utf16(output: &mut Vec<u8>, name: &str) {
+  for unit in name.encode_utf16().chain(core::iter::once(0)) {
+    output.extend_from_slice(&unit.to_be_bytes());
+  }
+}
+
+// ASE name lengths are u16 and count the trailing NUL, so longer names are cut short, without
+// splitting a surrogate pair.
+fn ase_name(name: &str) -> Vec<u8> {
+  let mut units: Vec<u16> = name.encode_utf16().take(u16::MAX as usize - 1).collect();
+  if units
+    .last()
+    .is_some_and(|unit| (0xd800..0xdc00).contains(unit))
+  {
+    units.pop();
+  }
+  let mut output = ((units.len() + 1) as u16).to_be_bytes().to_vec();
+  for unit in units.iter().chain(core::iter::once(&0)) {
+    output.extend_from_slice(&unit.to_be_bytes());
+  }
+  output
+}
+
+fn read_ase(bytes: &[u8]) -> Result<Palette, ErrorCode> {
+  let mut reader = Reader::new(bytes, PaletteFormat::Ase);
+  if reader.take(4, "ASEF signature")? != b"ASEF" {
+    return Err(error(PaletteFormat::Ase, 0, "ASEF signature"));
+  }
+  reader.u16("a major version")?;
+  reader.u16("a minor version")?;
+  let count = reader.u32("a block count")?;
+
+  let mut palette = Palette::new();
+  let mut group = None;
+  for _ in 0..count {
+    let kind = reader.u16("a block type")?;
+    let length = reader.u32("a block length")? as usize;
+    let end = reader
+      .position
+      .checked_add(length)
+      .filter(|end| *end <= bytes.len())
+      .ok_or_else(|| error(PaletteFormat::Ase, reader.position, "a complete block"))?;
+
+    match kind {
+      ASE_GROUP_START => {
+        let length = reader.u16("a name length")? as usize;
+        group = Some(reader.utf16(length)?);
+      }
+      ASE_GROUP_END => group = None,
+      ASE_COLOUR => {
+        let length = reader.u16("a name length")? as usize;
+        let name = reader.utf16(length)?;
+        let model_position = reader.position;
+        let model = reader.take(4, "a colour model")?;
+        let mut values = |count: usize| -> Result<Vec<f32>, ErrorCode> {
+          (0..count).map(|_| reader.f32("a colour value")).collect()
+        };
+        let swatch = match model {
+          b"RGB " => {
+            let rgb = values(3)?;
+            Swatch::new(
+              &name,
+              &RgbColour::from_tuple(channel(rgb[0]), channel(rgb[1]), channel(rgb[2])),
+            )
+          }
+          b"CMYK" => {
+            let cmyk = values(4)?;
+            Swatch::new(&name, &from_cmyk(cmyk[0], cmyk[1], cmyk[2], cmyk[3]))
+          }
+          b"LAB " => {
+            let lab = values(3)?;
+            Swatch::from_lab(&name, &LabColour::new(lab[0] * 100.0, lab[1], lab[2]))
+          }
+          b"Gray" => {
+            let grey = channel(values(1)?[0]);
+            Swatch::new(&name, &RgbColour::from_tuple(grey, grey, grey))
+          }
+          _ => {
+            return Err(error(
+              PaletteFormat::Ase,
+              model_position,
+              "RGB, CMYK, LAB or Gray colour model",
+            ))
+          }
+        };
+        palette.push(&match &group {
+          Some(group) => swatch.with_group(group),
+          None => swatch,
+        });
+      }
+      _ => {}
+    }
+    reader.position = end;
+  }
+  Ok(palette)
+}
+
+fn write_ase(palette: &Palette) -> Vec<u8> {
+  let mut blocks: Vec<(u16, Vec<u8>)> = vec![];
+  let mut group: Option<&String> = None;
+  for swatch in &palette.swatches {
+    if swatch.group.as_ref() != group {
+      if group.is_some() {
+        blocks.push((ASE_GROUP_END, vec![]));
+      }
+      if let Some(name) = &swatch.group {
+        blocks.push((ASE_GROUP_START, ase_name(name)));
+      }
+      group = swatch.group.as_ref();
+    }
+
+    let mut block = ase_name(&swatch.name);
+    let (model, values) = match swatch.colour {
+      SwatchColour::Rgb(colour) => (
+        b"RGB ",
+        vec![
+          colour.red as f32 / 255.0,
+          colour.green as f32 / 255.0,
+          colour.blue as f32 / 255.0,
+        ],
+      ),
+      SwatchColour::Lab(colour) => {
+        let (lightness, a, b) = colour.into();
+        (b"LAB ", vec![lightness / 100.0, a, b])
+      }
+    };
+    block.extend_from_slice(model);
+    for value in values {
+      block.extend_from_slice(&value.to_bits().to_be_bytes());
+    }
+    block.extend_from_slice(&ASE_NORMAL.to_be_bytes());
+    blocks.push((ASE_COLOUR, block));
+  }
+  if group.is_some() {
+    blocks.push((ASE_GROUP_END, vec![]));
+  }
+
+  let mut output = b"ASEF".to_vec();
+  output.extend_from_slice(&1u16.to_be_bytes());
+  output.extend_from_slice(&0u16.to_be_bytes());
+  output.extend_from_slice(&(blocks.len() as u32).to_be_bytes());
+  for (kind, block) in blocks {
+    output.extend_from_slice(&kind.to_be_bytes());
+    output.extend_from_slice(&(block.len() as u32).to_be_bytes());
+    output.extend_from_slice(&block);
+  }
+  output
+}
+
+fn from_cmyk(cyan: f32, magenta: f32, yellow: f32, black: f32) -> RgbColour {
+  RgbColour::from_tuple(
+    channel((1.0 - cyan) * (1.0 - black)),
+    channel((1.0 - magenta) * (1.0 - black)),
+    channel((1.0 - yellow) * (1.0 - black)),
+  )
+}
+
+fn read_aco_section(reader: &mut Reader, version: u16) -> Result<Vec<Swatch>, ErrorCode> {
+  let count = reader.u16("a colour count")?;
+  let mut swatches = vec![];
+  for _ in 0..count {
+    let space_position = reader.position;
+    let space = reader.u16("a colour space")?;
+    let mut values = [0u16; 4];
+    for value in values.iter_mut() {
+      *value = reader.u16("a colour value")?;
+    }
+    let name = if version == 2 {
+      let length = reader.u32("a name length")? as usize;
+      reader.utf16(length)?
+    } else {
+      String::new()
+    };
+
+    let unit = |value: u16| value as f32 / 65535.0;
+    swatches.push(match space {
+      ACO_RGB => Swatch::new(
+        &name,
+        &RgbColour::from_tuple(
+          channel(unit(values[0])),
+          channel(unit(values[1])),
+          channel(unit(values[2])),
+        ),
+      ),
+      ACO_HSB => {
+        let (saturation, brightness) = (unit(values[1]), unit(values[2]));
+        let lightness = brightness * (1.0 - saturation / 2.0);
+        let saturation = if lightness > 0.0 && lightness < 1.0 {
+          (brightness - lightness) / lightness.min(1.0 - lightness)
+        } else {
+          0.0
+        };
+        let hue = (unit(values[0]) * 360.0).round() as u32 % 360;
+        Swatch::new(
+          &name,
+          &HslColour::new(hue, saturation * 100.0, lightness * 100.0).to_rgb(),
+        )
+      }
+      // Photoshop stores ink amounts inverted, so 65535 is no ink.
+      ACO_CMYK => Swatch::new(
+        &name,
+        &from_cmyk(
+          1.0 - unit(values[0]),
+          1.0 - unit(values[1]),
+          1.0 - unit(values[2]),
+          1.0 - unit(values[3]),
+        ),
+      ),
+      ACO_LAB => Swatch::from_lab(
+        &name,
+        &LabColour::new(
+          values[0] as f32 / 100.0,
+          values[1] as i16 as f32 / 100.0,
+          values[2] as i16 as f32 / 100.0,
+        ),
+      ),
+      // Grey is a percentage of black ink, from 0 to 10000.
+      ACO_GREY => {
+        let grey = channel(1.0 - values[0] as f32 / 10000.0);
+        Swatch::new(&name, &RgbColour::from_tuple(grey, grey, grey))
+      }
+      _ => {
+        return Err(error(
+          PaletteFormat::Aco,
+          space_position,
+          "an RGB, HSB, CMYK, Lab or grayscale colour space",
+        ))
+      }
+    });
+  }
+  Ok(swatches)
+}
+
+// Version 1 files may be followed by a version 2 section carrying names, which wins when present.
+fn read_aco(bytes: &[u8]) -> Result<Palette, ErrorCode> {
+  let mut reader = Reader::new(bytes, PaletteFormat::Aco);
+  let mut swatches = vec![];
+  while reader.remaining() > 0 {
+    let position = reader.position;
+    match reader.u16("a version")? {
+      version @ (1 | 2) => swatches = read_aco_section(&mut reader, version)?,
+      _ => return Err(error(PaletteFormat::Aco, position, "version 1 or 2")),
+    }
+  }
+
+  Ok(Palette {
+    name: None,
+    swatches,
+  })
+}
+
+fn write_aco(palette: &Palette) -> Vec<u8> {
+  let mut output = vec![];
+  for version in [1u16, 2] {
+    output.extend_from_slice(&version.to_be_bytes());
+    // The swatch count is a u16, so anything past that is left out.
+    let swatches = &palette.swatches[..palette.len().min(u16::MAX as usize)];
+    output.extend_from_slice(&(swatches.len() as u16).to_be_bytes());
+    for swatch in swatches {
+      let (space, values) = match swatch.colour {
+        SwatchColour::Rgb(colour) => (
+          ACO_RGB,
+          [
+            colour.red as u16 * 257,
+            colour.green as u16 * 257,
+            colour.blue as u16 * 257,
+            0,
+          ],
+        ),
+        SwatchColour::Lab(colour) => {
+          let (lightness, a, b) = colour.into();
+          (
+            ACO_LAB,
+            [
+              (lightness * 100.0).round() as u16,
+              (a * 100.0).round() as i16 as u16,
+              (b * 100.0).round() as i16 as u16,
+              0,
+            ],
+          )
+        }
+      };
+      output.extend_from_slice(&space.to_be_bytes());
+      for value in values {
+        output.extend_from_slice(&value.to_be_bytes());
+      }
+      if version == 2 {
+        output.extend_from_slice(&((swatch.name.encode_utf16().count() + 1) as u32).to_be_bytes());
+        push_utf16(&mut output, &swatch.name);
+      }
+    }
+  }
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn palette() -> Palette {
+    let mut palette = Palette::new();
+    palette.set_name("Test");
+    palette.push(&Swatch::new("Red", &RgbColour::from_tuple(255, 0, 0)));
+    palette.push(&Swatch::new("Teal", &RgbColour::from_tuple(0, 128, 128)).with_group("Cool"));
+    palette.push(&Swatch::from_lab("Lab", &LabColour::new(50.0, 20.0, -30.0)).with_group("Cool"));
+    palette
+  }
+
+  fn assert_colours(actual: &Palette, expected: &Palette) {
+    assert_eq!(actual.colours(), expected.colours());
+  }
+
+  mod gpl {
+    use super::*;
+
+    #[test]
+    fn read() {
+      let gpl = b"GIMP Palette\nName: Basics\nColumns: 2\n# comment\n255   0   0\tRed\n  0 128 128 Dark teal\n";
+      let palette = Palette::read(gpl, PaletteFormat::Gpl).unwrap();
+      assert_eq!(palette.name(), Some("Basics".to_string()));
+      assert_eq!(palette.len(), 2);
+      assert_eq!(palette.get(1).unwrap().name(), "Dark teal");
+      assert_eq!(
+        palette.get(1).unwrap().to_rgb(),
+        RgbColour::from_tuple(0, 128, 128)
+      );
+    }
+
+    #[test]
+    fn round_trip() {
+      let palette = palette();
+      let read = Palette::parse(&palette.write(PaletteFormat::Gpl)).unwrap();
+      assert_eq!(read.name(), palette.name());
+      assert_eq!(read.get(0).unwrap().name(), "Red");
+      assert_colours(&read, &palette);
+    }
+
+    #[test]
+    fn invalid() {
+      assert_eq!(
+        Palette::read(b"GIMP Palette\n255 x 0 Red\n", PaletteFormat::Gpl),
+        Err(ErrorCode::InvalidPalette {
+          format: "GPL".to_string(),
+          position: 17,
+          expected: "a channel from 0 to 255".to_string(),
+        })
+      );
+    }
+  }
+
+  mod ase {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+      let palette = palette();
+      let bytes = palette.write(PaletteFormat::Ase);
+      assert_eq!(detect_palette_format(&bytes), Some(PaletteFormat::Ase));
+
+      let read = Palette::parse(&bytes).unwrap();
+      assert_eq!(read.len(), 3);
+      assert_eq!(read.get(0).unwrap().group(), None);
+      assert_eq!(read.get(1).unwrap().group(), Some("Cool".to_string()));
+      assert_eq!(read.get(2).unwrap().name(), "Lab");
+      assert!(read.get(2).unwrap().is_lab());
+      assert_eq!(
+        read.get(2).unwrap().to_lab(),
+        LabColour::new(50.0, 20.0, -30.0)
+      );
+      assert_colours(&read, &palette);
+    }
+
+    #[test]
+    fn truncated() {
+      let bytes = palette().write(PaletteFormat::Ase);
+      assert!(Palette::parse(&bytes[..bytes.len() - 4]).is_err());
+    }
+
+    #[test]
+    fn long_names() {
+      let long = "a".repeat(70_000);
+      let split = format!("{}\u{1f600}", "b".repeat(65_533));
+      let mut palette = Palette::new();
+      palette.push(&Swatch::new(&long, &RgbColour::from_tuple(255, 0, 0)).with_group(&long));
+      palette.push(&Swatch::new(&split, &RgbColour::from_tuple(0, 0, 255)));
+      let read = Palette::parse(&palette.write(PaletteFormat::Ase)).unwrap();
+      assert_eq!(read.get(0).unwrap().name(), "a".repeat(65_534));
+      assert_eq!(read.get(0).unwrap().group(), Some("a".repeat(65_534)));
+      assert_eq!(read.get(1).unwrap().name(), "b".repeat(65_533));
+    }
+
+    #[test]
+    fn oversized_block_length() {
+      let mut bytes = b"ASEF\0\x01\0\0\0\0\0\x01".to_vec();
+      bytes.extend_from_slice(&ASE_COLOUR.to_be_bytes());
+      bytes.extend_from_slice(&0xFFFF_FFFFu32.to_be_bytes());
+      assert_eq!(
+        Palette::read(&bytes, PaletteFormat::Ase),
+        Err(ErrorCode::InvalidPalette {
+          format: "ASE".to_string(),
+          position: 18,
+          expected: "a complete block".to_string(),
+        })
+      );
+    }
+  }
+
+  mod aco {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+      let palette = palette();
+      let read = Palette::parse(&palette.write(PaletteFormat::Aco)).unwrap();
+      assert_eq!(read.get(1).unwrap().name(), "Teal");
+      assert_eq!(
+        read.get(2).unwrap().to_lab(),
+        LabColour::new(50.0, 20.0, -30.0)
+      );
+      assert_colours(&read, &palette);
+    }
+
+    #[test]
+    fn swatch_count_limit() {
+      let colours = vec![RgbColour::from_tuple(1, 2, 3); u16::MAX as usize + 1];
+      let bytes = Palette::from_colours(colours).write(PaletteFormat::Aco);
+      assert_eq!(Palette::parse(&bytes).unwrap().len(), u16::MAX as usize);
+    }
+
+    #[test]
+    fn name_length_overflow() {
+      let mut reader = Reader::new(&[0, 0x61], PaletteFormat::Aco);
+      assert_eq!(
+        reader.utf16(usize::MAX / 2 + 1),
+        Err(ErrorCode::InvalidPalette {
+          format: "ACO".to_string(),
+          position: 0,
+          expected: "a UTF-16 name".to_string(),
+        })
+      );
+    }
+
+    #[test]
+    fn version_1_spaces() {
+      let mut bytes = vec![0, 1, 0, 3];
+      for (space, values) in [
+        (ACO_HSB, [0u16, 65535, 65535, 0]),
+        (ACO_CMYK, [65535, 0, 0, 65535]),
+        (ACO_GREY, [10000, 0, 0, 0]),
+      ] {
+        bytes.extend_from_slice(&space.to_be_bytes());
+        for value in values {
+          bytes.extend_from_slice(&value.to_be_bytes());
+        }
+      }
+      assert_eq!(
+        Palette::read(&bytes, PaletteFormat::Aco).unwrap().colours(),
+        vec![
+          RgbColour::from_tuple(255, 0, 0),
+          RgbColour::from_tuple(255, 0, 0),
+          RgbColour::from_tuple(0, 0, 0),
+        ]
+      );
+    }
+  }
+
+  mod sort {
+    use super::*;
+
+    fn colours(palette: &Palette) -> Vec<String> {
+      palette.colours().iter().map(RgbColour::to_hex).collect()
+    }
+
+    fn unsorted() -> Palette {
+      Palette::from_colours(vec![
+        RgbColour::from_tuple(0, 0, 255),
+        RgbColour::from_tuple(255, 255, 255),
+        RgbColour::from_tuple(255, 0, 0),
+        RgbColour::from_tuple(0, 0, 0),
+        RgbColour::from_tuple(0, 255, 0),
+      ])
+    }
+
+    #[test]
+    fn hue() {
+      let mut palette = unsorted();
+      palette.sort(PaletteOrder::Hue);
+      assert_eq!(
+        colours(&palette),
+        vec!["000000", "ffffff", "ff0000", "00ff00", "0000ff"]
+      );
+    }
+
+    #[test]
+    fn lightness_and_luminance() {
+      let mut palette = unsorted();
+      palette.sort(PaletteOrder::Lightness);
+      assert_eq!(
+        colours(&palette),
+        vec!["000000", "0000ff", "ff0000", "00ff00", "ffffff"]
+      );
+      palette.sort(PaletteOrder::Luminance);
+      assert_eq!(
+        colours(&palette),
+        vec!["000000", "0000ff", "ff0000", "00ff00", "ffffff"]
+      );
+    }
+
+    #[test]
+    fn hilbert_keeps_neighbours_together() {
+      let mut palette = Palette::from_colours(vec![
+        RgbColour::from_tuple(250, 0, 0),
+        RgbColour::from_tuple(0, 0, 250),
+        RgbColour::from_tuple(255, 0, 0),
+        RgbColour::from_tuple(0, 0, 255),
+      ]);
+      palette.sort(PaletteOrder::Hilbert);
+      let hex = colours(&palette);
+      let position = |value: &str| hex.iter().position(|hex| hex == value).unwrap() as i32;
+      assert_eq!((position("fa0000") - position("ff0000")).abs(), 1);
+      assert_eq!((position("0000fa") - position("0000ff")).abs(), 1);
+    }
+  }
+
+  mod spacing {
+    use super::*;
+
+    fn palette() -> Palette {
+      Palette::from_colours(vec![
+        RgbColour::from_tuple(255, 0, 0),
+        RgbColour::from_tuple(254, 1, 0),
+        RgbColour::from_tuple(0, 0, 255),
+      ])
+    }
+
+    #[test]
+    fn dedupe() {
+      let mut palette = palette();
+      palette.dedupe(1.0, Metric::Ciede2000);
+      assert_eq!(
+        palette.colours(),
+        vec![
+          RgbColour::from_tuple(255, 0, 0),
+          RgbColour::from_tuple(0, 0, 255)
+        ]
+      );
+    }
+
+    #[test]
+    fn close_pairs() {
+      let palette = palette();
+      let pairs = palette.close_pairs(2.0, Metric::Ciede2000);
+      assert_eq!(pairs.len(), 1);
+      assert_eq!((pairs[0].a, pairs[0].b), (0, 1));
+      assert_eq!(
+        palette.min_distance(Metric::Ciede2000),
+        Some(pairs[0].distance)
+      );
+      assert_eq!(Palette::new().min_distance(Metric::Lab), None);
+    }
+
+    #[test]
+    fn distance_matrix() {
+      let palette = palette();
+      let matrix = palette.distance_matrix(Metric::Rgb);
+      assert_eq!(matrix.len(), 9);
+      assert_eq!(matrix[0], 0.0);
+      assert_eq!(matrix[2], matrix[6]);
+      assert!((matrix[1] - 2.0f32.sqrt()).abs() < 0.0001);
+    }
+  }
+
+  mod text_formats {
+    use super::*;
+
+    #[test]
+    fn paint_net() {
+      let bytes = palette().write(PaletteFormat::PaintNet);
+      assert!(String::from_utf8(bytes.clone())
+        .unwrap()
+        .contains("FFFF0000\r\n"));
+      assert_colours(&Palette::parse(&bytes).unwrap(), &palette());
+    }
+
+    #[test]
+    fn paint_net_multi_byte() {
+      assert_eq!(
+        Palette::read("; Colours\naé12345\n".as_bytes(), PaletteFormat::PaintNet),
+        Err(ErrorCode::InvalidPalette {
+          format: "Paint.NET".to_string(),
+          position: 10,
+          expected: "an AARRGGBB hex colour".to_string(),
+        })
+      );
+    }
+
+    #[test]
+    fn jasc_pal() {
+      let bytes = palette().write(PaletteFormat::JascPal);
+      assert!(bytes.starts_with(b"JASC-PAL\r\n0100\r\n3\r\n255 0 0\r\n"));
+      assert_colours(&Palette::parse(&bytes).unwrap(), &palette());
+    }
+
+    #[test]
+    fn undetected() {
+      assert!(Palette::parse(b"not a palette").is_err());
+      assert_eq!(detect_palette_format(b""), None);
+      assert_eq!(detect_palette_format(b"FF00"), None);
+      assert_eq!(
+        detect_palette_format(b"FFFF0000\r\n"),
+        Some(PaletteFormat::PaintNet)
+      );
+    }
+  }
+}
@@ -0,0 +1,7 @@
+// The parts of the std prelude that come from alloc, for modules that also build under no_std.
+pub(crate) use alloc::boxed::Box;
+pub(crate) use alloc::format;
+pub(crate) use alloc::string::String;
+pub(crate) use alloc::string::ToString;
+pub(crate) use alloc::vec;
+pub(crate) use alloc::vec::Vec;
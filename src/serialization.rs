@@ -0,0 +1,364 @@
+use crate::colour::HslColour;
+use crate::colour::LabColour;
+use crate::colour::RgbColour;
+use crate::error_code::ErrorCode;
+use crate::format::FormatOptions;
+use serde::de;
+use serde::ser::SerializeStruct;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use std::fmt;
+use std::marker::PhantomData;
+
+// Colours that can be written as a string or a struct of components, and read back from either.
+pub trait SerdeColour: Sized + Copy {
+  const NAME: &'static str;
+  const FIELDS: &'static [&'static str; 3];
+
+  fn format(&self, options: &FormatOptions) -> String;
+  fn parse(value: &str) -> Result<Self, ErrorCode>;
+  fn serialize_components<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+  fn from_components<E: de::Error>(components: [f64; 3]) -> Result<Self, E>;
+}
+
+impl SerdeColour for RgbColour {
+  const NAME: &'static str = "RgbColour";
+  const FIELDS: &'static [&'static str; 3] = &["red", "green", "blue"];
+
+  fn format(&self, options: &FormatOptions) -> String {
+    RgbColour::format(self, options)
+  }
+
+  fn parse(value: &str) -> Result<Self, ErrorCode> {
+    RgbColour::parse(value)
+  }
+
+  fn serialize_components<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut state = serializer.serialize_struct(Self::NAME, 3)?;
+    state.serialize_field("red", &self.red)?;
+    state.serialize_field("green", &self.green)?;
+    state.serialize_field("blue", &self.blue)?;
+    state.end()
+  }
+
+  fn from_components<E: de::Error>(components: [f64; 3]) -> Result<Self, E> {
+    let channel = |value: f64| {
+      if value.fract() == 0.0 && (0.0..=255.0).contains(&value) {
+        Ok(value as u8)
+      } else {
+        Err(E::invalid_value(
+          de::Unexpected::Float(value),
+          &"an integer between 0 and 255",
+        ))
+      }
+    };
+
+    Ok(RgbColour::from_tuple(
+      channel(components[0])?,
+      channel(components[1])?,
+      channel(components[2])?,
+    ))
+  }
+}
+
+impl SerdeColour for HslColour {
+  const NAME: &'static str = "HslColour";
+  const FIELDS: &'static [&'static str; 3] = &["hue", "saturation", "lightness"];
+
+  fn format(&self, options: &FormatOptions) -> String {
+    HslColour::format(self, options)
+  }
+
+  fn parse(value: &str) -> Result<Self, ErrorCode> {
+    value.parse()
+  }
+
+  fn serialize_components<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let (hue, saturation, lightness) = (*self).into();
+    let mut state = serializer.serialize_struct(Self::NAME, 3)?;
+    state.serialize_field("hue", &(hue as u32))?;
+    state.serialize_field("saturation", &saturation)?;
+    state.serialize_field("lightness", &lightness)?;
+    state.end()
+  }
+
+  fn from_components<E: de::Error>(components: [f64; 3]) -> Result<Self, E> {
+    Ok(HslColour::new(
+      (components[0].round() as i32).rem_euclid(360) as u32,
+      components[1] as f32,
+      components[2] as f32,
+    ))
+  }
+}
+
+impl SerdeColour for LabColour {
+  const NAME: &'static str = "LabColour";
+  const FIELDS: &'static [&'static str; 3] = &["lightness", "a", "b"];
+
+  fn format(&self, options: &FormatOptions) -> String {
+    LabColour::format(self, options)
+  }
+
+  fn parse(value: &str) -> Result<Self, ErrorCode> {
+    value.parse()
+  }
+
+  fn serialize_components<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let (lightness, a, b) = (*self).into();
+    let mut state = serializer.serialize_struct(Self::NAME, 3)?;
+    state.serialize_field("lightness", &lightness)?;
+    state.serialize_field("a", &a)?;
+    state.serialize_field("b", &b)?;
+    state.end()
+  }
+
+  fn from_components<E: de::Error>(components: [f64; 3]) -> Result<Self, E> {
+    Ok(LabColour::new(
+      components[0] as f32,
+      components[1] as f32,
+      components[2] as f32,
+    ))
+  }
+}
+
+struct ColourVisitor<T>(PhantomData<T>);
+
+impl<'de, T: SerdeColour> de::Visitor<'de> for ColourVisitor<T> {
+  type Value = T;
+
+  fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      formatter,
+      "a colour string or a struct with {}, {} and {}",
+      T::FIELDS[0],
+      T::FIELDS[1],
+      T::FIELDS[2]
+    )
+  }
+
+  fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+    T::parse(value).map_err(|error| {
+      let mut message = error.to_string();
+      let mut source = std::error::Error::source(&error);
+      while let Some(cause) = source {
+        message = format!("{}: {}", message, cause);
+        source = cause.source();
+      }
+      E::custom(message)
+    })
+  }
+
+  fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+    let mut components = [0.0; 3];
+    for (index, component) in components.iter_mut().enumerate() {
+      *component = seq
+        .next_element()?
+        .ok_or_else(|| de::Error::invalid_length(index, &self))?;
+    }
+    T::from_components(components)
+  }
+
+  fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+    let mut components = [None; 3];
+    while let Some(key) = map.next_key::<String>()? {
+      let index = T::FIELDS
+        .iter()
+        .position(|field| *field == key)
+        .ok_or_else(|| de::Error::unknown_field(&key, T::FIELDS))?;
+      if components[index].is_some() {
+        return Err(de::Error::duplicate_field(T::FIELDS[index]));
+      }
+      components[index] = Some(map.next_value::<f64>()?);
+    }
+
+    let mut values = [0.0; 3];
+    for (index, value) in values.iter_mut().enumerate() {
+      *value = components[index].ok_or_else(|| de::Error::missing_field(T::FIELDS[index]))?;
+    }
+    T::from_components(values)
+  }
+}
+
+fn deserialize_any<'de, D: Deserializer<'de>, T: SerdeColour>(
+  deserializer: D,
+) -> Result<T, D::Error> {
+  deserializer.deserialize_any(ColourVisitor(PhantomData))
+}
+
+// For use with `#[serde(with = "colour_fun::serialization::hex")]`.
+pub mod hex {
+  use super::*;
+
+  pub fn serialize<T: SerdeColour, S: Serializer>(
+    colour: &T,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&colour.format(&FormatOptions::new()))
+  }
+
+  pub fn deserialize<'de, T: SerdeColour, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<T, D::Error> {
+    deserialize_any(deserializer)
+  }
+}
+
+pub mod css {
+  use super::*;
+
+  pub fn serialize<T: SerdeColour, S: Serializer>(
+    colour: &T,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&colour.format(&FormatOptions::css()))
+  }
+
+  pub fn deserialize<'de, T: SerdeColour, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<T, D::Error> {
+    deserialize_any(deserializer)
+  }
+}
+
+pub mod components {
+  use super::*;
+
+  pub fn serialize<T: SerdeColour, S: Serializer>(
+    colour: &T,
+    serializer: S,
+  ) -> Result<S::Ok, S::Error> {
+    colour.serialize_components(serializer)
+  }
+
+  pub fn deserialize<'de, T: SerdeColour, D: Deserializer<'de>>(
+    deserializer: D,
+  ) -> Result<T, D::Error> {
+    deserialize_any(deserializer)
+  }
+}
+
+impl Serialize for RgbColour {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    hex::serialize(self, serializer)
+  }
+}
+
+impl Serialize for HslColour {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    css::serialize(self, serializer)
+  }
+}
+
+impl Serialize for LabColour {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    css::serialize(self, serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for RgbColour {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserialize_any(deserializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for HslColour {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserialize_any(deserializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for LabColour {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserialize_any(deserializer)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::comparison::ComparisonResult;
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  struct Theme {
+    primary: RgbColour,
+    #[serde(with = "css")]
+    secondary: RgbColour,
+    #[serde(with = "components")]
+    accent: HslColour,
+  }
+
+  fn theme() -> Theme {
+    Theme {
+      primary: RgbColour::from_tuple(102, 51, 153),
+      secondary: RgbColour::from_tuple(244, 43, 32),
+      accent: HslColour::new(270, 50.0, 40.0),
+    }
+  }
+
+  mod serialize {
+    use super::*;
+
+    #[test]
+    fn formats() {
+      assert_eq!(
+        serde_json::to_string(&theme()).unwrap(),
+        r##"{"primary":"#663399","secondary":"rgb(244 43 32)","accent":{"hue":270,"saturation":50.0,"lightness":40.0}}"##
+      );
+    }
+
+    #[test]
+    fn lab() {
+      assert_eq!(
+        serde_json::to_string(&LabColour::new(50.0, -20.5, 10.0)).unwrap(),
+        r#""lab(50 -20.5 10)""#
+      );
+    }
+
+    #[test]
+    fn comparison_result() {
+      let json = serde_json::to_string(&ComparisonResult(12.5, 5)).unwrap();
+      assert_eq!(json, "[12.5,5]");
+      assert_eq!(
+        serde_json::from_str::<ComparisonResult>(&json).unwrap(),
+        ComparisonResult(12.5, 5)
+      );
+    }
+  }
+
+  mod deserialize {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+      let json = serde_json::to_string(&theme()).unwrap();
+      assert_eq!(serde_json::from_str::<Theme>(&json).unwrap(), theme());
+    }
+
+    #[test]
+    fn any_parsable_format() {
+      let theme: Theme = serde_json::from_str(
+        r#"{"primary":"rebeccapurple","secondary":[244,43,32],"accent":"hsl(270deg, 50%, 40%)"}"#,
+      )
+      .unwrap();
+      assert_eq!(theme, self::theme());
+    }
+
+    #[test]
+    fn error_messages() {
+      let error = serde_json::from_str::<RgbColour>(r##""#F43C8X""##).unwrap_err();
+      assert!(error.to_string().starts_with(
+        "Invalid: could not parse #F43C8X as a colour: Invalid: found invalid character 'X' at position 6"
+      ));
+
+      let error = serde_json::from_str::<RgbColour>(r#"{"red":1,"green":2}"#).unwrap_err();
+      assert!(error.to_string().starts_with("missing field `blue`"));
+
+      let error = serde_json::from_str::<RgbColour>(r#"[1,2,300]"#).unwrap_err();
+      assert!(error
+        .to_string()
+        .contains("expected an integer between 0 and 255"));
+    }
+  }
+}
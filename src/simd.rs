@@ -0,0 +1,583 @@
+use crate::colour::SRGB_TO_LINEAR;
+use crate::colour::SRGB_TO_XYZ;
+use crate::colour::XYZ_TO_SRGB;
+use crate::matrix::Matrix;
+use crate::oklab::LMS_TO_OKLAB;
+use crate::oklab::LMS_TO_XYZ;
+use crate::oklab::OKLAB_TO_LMS;
+use crate::oklab::XYZ_TO_LMS;
+
+// Four f32 lanes: SSE2 on x86_64 (always available there), simd128 on wasm32 when the target
+// feature is enabled, and a plain array everywhere else. Every operation maps to a single IEEE
+// instruction with no fused multiply-add, so results match the scalar code lane for lane. The
+// array version is also built for tests, which check it against the native lanes.
+#[cfg(target_arch = "x86_64")]
+mod lanes {
+  use core::arch::x86_64::*;
+  use core::ops::{Add, Div, Mul, Sub};
+
+  // SAFETY, for every intrinsic below: SSE2 is part of the x86_64 baseline.
+  #[derive(Copy, Clone)]
+  pub(crate) struct F32x4(__m128);
+
+  impl F32x4 {
+    pub(crate) fn splat(value: f32) -> F32x4 {
+      F32x4(unsafe { _mm_set1_ps(value) })
+    }
+
+    pub(crate) fn from_array(values: [f32; 4]) -> F32x4 {
+      F32x4(unsafe { _mm_setr_ps(values[0], values[1], values[2], values[3]) })
+    }
+
+    pub(crate) fn to_array(self) -> [f32; 4] {
+      let mut values = [0.0; 4];
+      // `values` has room for the four unaligned floats written.
+      unsafe { _mm_storeu_ps(values.as_mut_ptr(), self.0) };
+      values
+    }
+
+    // Lanes of `then` where this lane is greater than `threshold`, and `otherwise` elsewhere.
+    pub(crate) fn select_gt(self, threshold: f32, then: F32x4, otherwise: F32x4) -> F32x4 {
+      F32x4(unsafe {
+        let mask = _mm_cmpgt_ps(self.0, _mm_set1_ps(threshold));
+        _mm_or_ps(_mm_and_ps(mask, then.0), _mm_andnot_ps(mask, otherwise.0))
+      })
+    }
+  }
+
+  impl Add for F32x4 {
+    type Output = F32x4;
+    fn add(self, other: F32x4) -> F32x4 {
+      F32x4(unsafe { _mm_add_ps(self.0, other.0) })
+    }
+  }
+
+  impl Sub for F32x4 {
+    type Output = F32x4;
+    fn sub(self, other: F32x4) -> F32x4 {
+      F32x4(unsafe { _mm_sub_ps(self.0, other.0) })
+    }
+  }
+
+  impl Mul for F32x4 {
+    type Output = F32x4;
+    fn mul(self, other: F32x4) -> F32x4 {
+      F32x4(unsafe { _mm_mul_ps(self.0, other.0) })
+    }
+  }
+
+  impl Div for F32x4 {
+    type Output = F32x4;
+    fn div(self, other: F32x4) -> F32x4 {
+      F32x4(unsafe { _mm_div_ps(self.0, other.0) })
+    }
+  }
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+mod lanes {
+  use core::arch::wasm32::*;
+  use core::ops::{Add, Div, Mul, Sub};
+
+  #[derive(Copy, Clone)]
+  pub(crate) struct F32x4(v128);
+
+  impl F32x4 {
+    pub(crate) fn splat(value: f32) -> F32x4 {
+      F32x4(f32x4_splat(value))
+    }
+
+    pub(crate) fn from_array(values: [f32; 4]) -> F32x4 {
+      F32x4(f32x4(values[0], values[1], values[2], values[3]))
+    }
+
+    pub(crate) fn to_array(self) -> [f32; 4] {
+      [
+        f32x4_extract_lane::<0>(self.0),
+        f32x4_extract_lane::<1>(self.0),
+        f32x4_extract_lane::<2>(self.0),
+        f32x4_extract_lane::<3>(self.0),
+      ]
+    }
+
+    pub(crate) fn select_gt(self, threshold: f32, then: F32x4, otherwise: F32x4) -> F32x4 {
+      let mask = f32x4_gt(self.0, f32x4_splat(threshold));
+      F32x4(v128_bitselect(then.0, otherwise.0, mask))
+    }
+  }
+
+  impl Add for F32x4 {
+    type Output = F32x4;
+    fn add(self, other: F32x4) -> F32x4 {
+      F32x4(f32x4_add(self.0, other.0))
+    }
+  }
+
+  impl Sub for F32x4 {
+    type Output = F32x4;
+    fn sub(self, other: F32x4) -> F32x4 {
+      F32x4(f32x4_sub(self.0, other.0))
+    }
+  }
+
+  impl Mul for F32x4 {
+    type Output = F32x4;
+    fn mul(self, other: F32x4) -> F32x4 {
+      F32x4(f32x4_mul(self.0, other.0))
+    }
+  }
+
+  impl Div for F32x4 {
+    type Output = F32x4;
+    fn div(self, other: F32x4) -> F32x4 {
+      F32x4(f32x4_div(self.0, other.0))
+    }
+  }
+}
+
+#[cfg(any(
+  test,
+  not(any(
+    target_arch = "x86_64",
+    all(target_arch = "wasm32", target_feature = "simd128")
+  ))
+))]
+mod portable {
+  use core::ops::{Add, Div, Mul, Sub};
+
+  #[derive(Copy, Clone)]
+  pub(crate) struct F32x4([f32; 4]);
+
+  impl F32x4 {
+    pub(crate) fn splat(value: f32) -> F32x4 {
+      F32x4([value; 4])
+    }
+
+    pub(crate) fn from_array(values: [f32; 4]) -> F32x4 {
+      F32x4(values)
+    }
+
+    pub(crate) fn to_array(self) -> [f32; 4] {
+      self.0
+    }
+
+    pub(crate) fn select_gt(self, threshold: f32, then: F32x4, otherwise: F32x4) -> F32x4 {
+      let mut values = otherwise.0;
+      for (lane, value) in values.iter_mut().enumerate() {
+        if self.0[lane] > threshold {
+          *value = then.0[lane];
+        }
+      }
+      F32x4(values)
+    }
+
+    fn zip(self, other: F32x4, operation: impl Fn(f32, f32) -> f32) -> F32x4 {
+      let mut values = self.0;
+      for (value, other) in values.iter_mut().zip(other.0) {
+        *value = operation(*value, other);
+      }
+      F32x4(values)
+    }
+  }
+
+  impl Add for F32x4 {
+    type Output = F32x4;
+    fn add(self, other: F32x4) -> F32x4 {
+      self.zip(other, |a, b| a + b)
+    }
+  }
+
+  impl Sub for F32x4 {
+    type Output = F32x4;
+    fn sub(self, other: F32x4) -> F32x4 {
+      self.zip(other, |a, b| a - b)
+    }
+  }
+
+  impl Mul for F32x4 {
+    type Output = F32x4;
+    fn mul(self, other: F32x4) -> F32x4 {
+      self.zip(other, |a, b| a * b)
+    }
+  }
+
+  impl Div for F32x4 {
+    type Output = F32x4;
+    fn div(self, other: F32x4) -> F32x4 {
+      self.zip(other, |a, b| a / b)
+    }
+  }
+}
+
+#[cfg(not(any(
+  target_arch = "x86_64",
+  all(target_arch = "wasm32", target_feature = "simd128")
+)))]
+use portable as lanes;
+
+use lanes::F32x4;
+
+type Channels = [F32x4; 3];
+
+impl F32x4 {
+  // A bit-level estimate refined by three Newton steps, within a few ulp of `cbrt`.
+  fn cbrt(self) -> F32x4 {
+    let estimate = self
+      .to_array()
+      .map(|value| f32::from_bits(value.abs().to_bits() / 3 + 0x2a51_4067).copysign(value));
+    let third = F32x4::splat(1.0 / 3.0);
+    let mut root = F32x4::from_array(estimate);
+    for _ in 0..3 {
+      root = (root + root + self / (root * root)) * third;
+    }
+    root
+  }
+
+  fn cube(self) -> F32x4 {
+    self * self * self
+  }
+}
+
+fn apply(matrix: &Matrix, [a, b, c]: Channels) -> Channels {
+  let row =
+    |row: &[f32; 3]| F32x4::splat(row[0]) * a + F32x4::splat(row[1]) * b + F32x4::splat(row[2]) * c;
+  [row(&matrix[0]), row(&matrix[1]), row(&matrix[2])]
+}
+
+// The linear value at which encoding rounds up to each 8-bit level, so encoding is a binary search
+// instead of a `powf` call per channel.
+#[rustfmt::skip]
+const ENCODE_THRESHOLDS: [f32; 255] = [
+  0.0001517635, 0.0004552905, 0.0007588175, 0.0010623444, 0.0013658715, 0.0016693984, 0.0019729254,
+  0.0022764523, 0.0025799794, 0.0028835065, 0.0031883009, 0.003509259, 0.0038483152, 0.004205748,
+  0.0045818323, 0.004976837, 0.005391024, 0.0058246506, 0.006277969, 0.0067512277, 0.007244668,
+  0.00775853, 0.008293048, 0.008848452, 0.009424971, 0.010022826, 0.010642237, 0.011283421,
+  0.011946591, 0.012631958, 0.01333973, 0.014070112, 0.014823303, 0.015599502, 0.016398909,
+  0.017221715, 0.018068112, 0.018938292, 0.019832443, 0.020750744, 0.021693382, 0.022660537,
+  0.023652388, 0.024669113, 0.025710884, 0.026777882, 0.02787027, 0.02898822, 0.0301319,
+  0.031301476, 0.03249712, 0.033718985, 0.034967244, 0.036242038, 0.037543546, 0.03887193,
+  0.04022732, 0.04160989, 0.043019786, 0.04445716, 0.04592217, 0.047414962, 0.04893568,
+  0.05048448, 0.052061513, 0.053666905, 0.055300806, 0.056963366, 0.05865472, 0.060375012,
+  0.0621244, 0.06390299, 0.06571093, 0.067548364, 0.06941543, 0.07131225, 0.07323897,
+  0.075195715, 0.07718262, 0.07919983, 0.08124745, 0.08332563, 0.08543449, 0.08757418,
+  0.089744784, 0.09194645, 0.09417932, 0.09644349, 0.0987391, 0.101066284, 0.103425145,
+  0.10581581, 0.108238414, 0.11069305, 0.11317987, 0.11569898, 0.11825049, 0.120834544,
+  0.123451225, 0.12610066, 0.12878297, 0.13149828, 0.13424669, 0.13702832, 0.13984329,
+  0.1426917, 0.14557368, 0.14848931, 0.15143874, 0.15442206, 0.15743938, 0.16049086,
+  0.16357651, 0.16669652, 0.16985095, 0.17303994, 0.17626359, 0.17952198, 0.18281525,
+  0.18614352, 0.18950684, 0.19290535, 0.19633916, 0.19980836, 0.20331305, 0.20685335,
+  0.21042934, 0.21404114, 0.21768884, 0.22137256, 0.22509238, 0.22884841, 0.23264082,
+  0.23646957, 0.24033482, 0.24423668, 0.24817525, 0.25215062, 0.25616288, 0.26021215,
+  0.26429853, 0.26842207, 0.27258292, 0.27678114, 0.28101683, 0.2852901, 0.28960103,
+  0.29394975, 0.2983363, 0.3027608, 0.30722338, 0.31172404, 0.31626296, 0.32084018,
+  0.32545584, 0.33010998, 0.33480275, 0.33953416, 0.34430438, 0.34911352, 0.35396156,
+  0.35884863, 0.36377484, 0.36874026, 0.37374502, 0.3787892, 0.3838728, 0.38899603,
+  0.39415893, 0.39936155, 0.40460405, 0.40988645, 0.41520888, 0.4205714, 0.42597407,
+  0.43141705, 0.43690038, 0.44242415, 0.44798842, 0.4535933, 0.45923892, 0.4649253,
+  0.47065252, 0.4764207, 0.48222992, 0.48808023, 0.49397177, 0.49990463, 0.5058788,
+  0.5118944, 0.5179515, 0.52405024, 0.5301906, 0.5363728, 0.5425968, 0.54886276,
+  0.5551707, 0.5615207, 0.56791294, 0.5743474, 0.58082414, 0.58734334, 0.59390503,
+  0.6005093, 0.60715616, 0.6138457, 0.62057817, 0.6273534, 0.6341716, 0.64103293,
+  0.6479373, 0.6548848, 0.6618756, 0.6689098, 0.6759875, 0.68310857, 0.69027317,
+  0.69748145, 0.7047335, 0.7120292, 0.71936893, 0.7267525, 0.73418015, 0.7416519,
+  0.74916774, 0.7567279, 0.76433235, 0.7719812, 0.7796745, 0.78741235, 0.7951948,
+  0.8030219, 0.81089383, 0.8188106, 0.8267722, 0.83477885, 0.8428305, 0.8509273,
+  0.8590692, 0.8672565, 0.8754891, 0.88376707, 0.8920907, 0.9004595, 0.90887433,
+  0.9173345, 0.92584074, 0.9343925, 0.9429905, 0.95163417, 0.9603241, 0.96905994,
+  0.9778422, 0.9866705, 0.9955453,
+];
+
+// Out of range values clip and NaN becomes 0, as in `RgbColour::from_linear`.
+fn encode(value: f32) -> u8 {
+  ENCODE_THRESHOLDS.partition_point(|threshold| *threshold <= value) as u8
+}
+
+// Converts four RGBA pixels at a time into three floats each, padding the last block with black.
+pub(crate) fn from_rgba(
+  pixels: &[u8],
+  output: &mut [f32],
+  transform: impl Fn(Channels) -> Channels,
+) {
+  for (pixels, output) in pixels.chunks(16).zip(output.chunks_mut(12)) {
+    let mut channels = [[0.0; 4]; 3];
+    for (lane, pixel) in pixels.chunks_exact(4).enumerate() {
+      for (channel, level) in channels.iter_mut().zip(pixel) {
+        channel[lane] = SRGB_TO_LINEAR[*level as usize];
+      }
+    }
+
+    let channels = transform(channels.map(F32x4::from_array)).map(F32x4::to_array);
+    for (lane, values) in output.chunks_exact_mut(3).enumerate() {
+      for (value, channel) in values.iter_mut().zip(&channels) {
+        *value = channel[lane];
+      }
+    }
+  }
+}
+
+// The reverse of `from_rgba`, writing the colour channels and leaving alpha alone.
+pub(crate) fn to_rgba(input: &[f32], pixels: &mut [u8], transform: impl Fn(Channels) -> Channels) {
+  for (input, pixels) in input.chunks(12).zip(pixels.chunks_mut(16)) {
+    let mut channels = [[0.0; 4]; 3];
+    for (lane, values) in input.chunks_exact(3).enumerate() {
+      for (channel, value) in channels.iter_mut().zip(values) {
+        channel[lane] = *value;
+      }
+    }
+
+    let channels = transform(channels.map(F32x4::from_array)).map(F32x4::to_array);
+    for (lane, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+      for (level, channel) in pixel.iter_mut().zip(&channels) {
+        *level = encode(channel[lane]);
+      }
+    }
+  }
+}
+
+pub(crate) fn linear(channels: Channels) -> Channels {
+  channels
+}
+
+pub(crate) fn linear_to_xyz(channels: Channels) -> Channels {
+  apply(&SRGB_TO_XYZ, channels)
+}
+
+pub(crate) fn xyz_to_linear(channels: Channels) -> Channels {
+  apply(&XYZ_TO_SRGB, channels)
+}
+
+// Mirrors `XyzColour::to_lab` and `LabColour::to_xyz`.
+pub(crate) fn linear_to_lab(channels: Channels) -> Channels {
+  let [x, y, z] = linear_to_xyz(channels);
+  let f = |value: F32x4| {
+    let linear = F32x4::splat(7.787) * value + F32x4::splat(16.0 / 116.0);
+    value.select_gt(0.008856, value.cbrt(), linear)
+  };
+  let (x, y, z) = (
+    f(x / F32x4::splat(0.95047)),
+    f(y / F32x4::splat(1.0)),
+    f(z / F32x4::splat(1.08883)),
+  );
+  [
+    F32x4::splat(116.0) * y - F32x4::splat(16.0),
+    F32x4::splat(500.0) * (x - y),
+    F32x4::splat(200.0) * (y - z),
+  ]
+}
+
+pub(crate) fn lab_to_linear([lightness, a, b]: Channels) -> Channels {
+  let y = (lightness + F32x4::splat(16.0)) / F32x4::splat(116.0);
+  let x = a / F32x4::splat(500.0) + y;
+  let z = y - b / F32x4::splat(200.0);
+  let f = |value: F32x4| {
+    let cube = value.cube();
+    let linear = (value - F32x4::splat(16.0 / 116.0)) / F32x4::splat(7.787);
+    cube.select_gt(0.008856, cube, linear)
+  };
+  xyz_to_linear([
+    f(x) * F32x4::splat(0.95047),
+    f(y),
+    f(z) * F32x4::splat(1.08883),
+  ])
+}
+
+// Mirrors `OklabColour::from_xyz` and `OklabColour::to_xyz`.
+pub(crate) fn linear_to_oklab(channels: Channels) -> Channels {
+  let [l, m, s] = apply(&XYZ_TO_LMS, linear_to_xyz(channels));
+  apply(&LMS_TO_OKLAB, [l.cbrt(), m.cbrt(), s.cbrt()])
+}
+
+pub(crate) fn oklab_to_linear(channels: Channels) -> Channels {
+  let [l, m, s] = apply(&OKLAB_TO_LMS, channels);
+  xyz_to_linear(apply(&LMS_TO_XYZ, [l.cube(), m.cube(), s.cube()]))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::colour::LabColour;
+  use crate::colour::RgbColour;
+  use crate::oklab::OklabColour;
+
+  // Every 17th level of each channel, plus a partial final block.
+  fn pixels() -> Vec<u8> {
+    let mut pixels = vec![];
+    for red in (0..=255).step_by(17) {
+      for green in (0..=255).step_by(17) {
+        for blue in (0..=255).step_by(17) {
+          pixels.extend([red, green, blue, 255]);
+        }
+      }
+    }
+    pixels.extend([1, 2, 3, 4, 250, 251, 252, 253]);
+    pixels
+  }
+
+  fn convert(pixels: &[u8], transform: impl Fn(Channels) -> Channels) -> Vec<f32> {
+    let mut output = vec![0.0; pixels.len() / 4 * 3];
+    from_rgba(pixels, &mut output, transform);
+    output
+  }
+
+  fn colours(pixels: &[u8]) -> impl Iterator<Item = RgbColour> + '_ {
+    pixels
+      .chunks_exact(4)
+      .map(|pixel| RgbColour::from_tuple(pixel[0], pixel[1], pixel[2]))
+  }
+
+  mod from_rgba {
+    use super::*;
+
+    #[test]
+    fn linear_and_xyz_are_exact() {
+      let pixels = pixels();
+      let linear = convert(&pixels, super::linear);
+      let xyz = convert(&pixels, linear_to_xyz);
+      for (index, colour) in colours(&pixels).enumerate() {
+        let expected = colour.to_linear();
+        assert_eq!(
+          &linear[index * 3..index * 3 + 3],
+          &[expected.0, expected.1, expected.2]
+        );
+        let expected = colour.to_xyz();
+        assert_eq!(
+          &xyz[index * 3..index * 3 + 3],
+          &[expected.x, expected.y, expected.z]
+        );
+      }
+    }
+
+    #[test]
+    fn lab_within_tolerance() {
+      let pixels = pixels();
+      let lab = convert(&pixels, linear_to_lab);
+      for (index, colour) in colours(&pixels).enumerate() {
+        let expected: (f32, f32, f32) = colour.to_lab().into();
+        let actual = &lab[index * 3..index * 3 + 3];
+        assert!(
+          (actual[0] - expected.0).abs() < 1e-4
+            && (actual[1] - expected.1).abs() < 1e-4
+            && (actual[2] - expected.2).abs() < 1e-4,
+          "{:?} != {:?}",
+          actual,
+          expected
+        );
+      }
+    }
+
+    #[test]
+    fn oklab_within_tolerance() {
+      let pixels = pixels();
+      let oklab = convert(&pixels, linear_to_oklab);
+      for (index, colour) in colours(&pixels).enumerate() {
+        let expected = OklabColour::from_rgb(&colour);
+        let actual = &oklab[index * 3..index * 3 + 3];
+        assert!(
+          (actual[0] - expected.lightness).abs() < 1e-6
+            && (actual[1] - expected.a).abs() < 1e-6
+            && (actual[2] - expected.b).abs() < 1e-6,
+          "{:?} != {:?}",
+          actual,
+          expected
+        );
+      }
+    }
+  }
+
+  mod to_rgba {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+      let pixels = pixels();
+      for (forward, reverse) in [
+        (
+          linear as fn(Channels) -> Channels,
+          linear as fn(Channels) -> Channels,
+        ),
+        (linear_to_xyz, xyz_to_linear),
+        (linear_to_lab, lab_to_linear),
+        (linear_to_oklab, oklab_to_linear),
+      ] {
+        let mut output = vec![0; pixels.len()];
+        to_rgba(&convert(&pixels, forward), &mut output, reverse);
+        for (actual, expected) in output.chunks_exact(4).zip(pixels.chunks_exact(4)) {
+          assert_eq!(actual[..3], expected[..3]);
+          assert_eq!(actual[3], 0);
+        }
+      }
+    }
+
+    #[test]
+    fn matches_scalar_encoding() {
+      let mut input = vec![];
+      for step in 0..3000 {
+        let lightness = step as f32 / 30.0;
+        input.extend([lightness, lightness - 50.0, 50.0 - lightness]);
+      }
+      let mut output = vec![0; input.len() / 3 * 4];
+      to_rgba(&input, &mut output, lab_to_linear);
+      for (values, actual) in input.chunks_exact(3).zip(output.chunks_exact(4)) {
+        let expected = LabColour::new(values[0], values[1], values[2]).to_rgb();
+        assert_eq!(actual[..3], [expected.red, expected.green, expected.blue]);
+      }
+    }
+  }
+
+  mod portable {
+    use super::*;
+    use crate::simd::portable::F32x4 as PortableF32x4;
+
+    #[test]
+    fn matches_native_lanes() {
+      let values = ([0.25, -3.5, 1e-7, 0.008856], [3.0, 0.1, -2.0, 7.787]);
+      let (a, b) = (F32x4::from_array(values.0), F32x4::from_array(values.1));
+      let (c, d) = (
+        PortableF32x4::from_array(values.0),
+        PortableF32x4::from_array(values.1),
+      );
+      assert_eq!((a + b).to_array(), (c + d).to_array());
+      assert_eq!((a - b).to_array(), (c - d).to_array());
+      assert_eq!((a * b).to_array(), (c * d).to_array());
+      assert_eq!((a / b).to_array(), (c / d).to_array());
+      assert_eq!(
+        a.select_gt(0.008856, a, b).to_array(),
+        c.select_gt(0.008856, c, d).to_array()
+      );
+      assert_eq!(
+        F32x4::splat(0.5).to_array(),
+        PortableF32x4::splat(0.5).to_array()
+      );
+    }
+  }
+
+  mod encode {
+    use super::*;
+    use crate::colour::srgb_to_linear;
+
+    #[test]
+    fn thresholds_match_transfer_function() {
+      for (level, threshold) in ENCODE_THRESHOLDS.iter().enumerate() {
+        let expected = srgb_to_linear((level as f32 + 0.5) / 255.0);
+        assert_eq!(*threshold, expected, "{}", level);
+      }
+    }
+
+    #[test]
+    fn matches_from_linear() {
+      let mut mismatches = 0;
+      for step in -100..=100_100 {
+        let value = step as f32 / 100_000.0;
+        let expected = RgbColour::from_linear(value, 0.0, 0.0).red;
+        let actual = encode(value);
+        // Values within an ulp or two of a rounding boundary may land either side of it.
+        assert!((actual as i32 - expected as i32).abs() <= 1, "{}", value);
+        if actual != expected {
+          mismatches += 1;
+        }
+      }
+      assert!(mismatches < 10, "{}", mismatches);
+      assert_eq!(encode(f32::NAN), 0);
+    }
+  }
+}
@@ -0,0 +1,100 @@
+use crate::colour::RgbColour;
+use crate::matrix::{apply, Matrix};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+// Machado, Oliveira & Fernandes (2009) matrices at full severity, applied to linear sRGB.
+const PROTANOPIA: Matrix = [
+  [0.152_286, 1.052_583, -0.204_868],
+  [0.114_503, 0.786_281, 0.099_216],
+  [-0.003_882, -0.048_116, 1.051_998],
+];
+const DEUTERANOPIA: Matrix = [
+  [0.367_322, 0.860_646, -0.227_968],
+  [0.280_085, 0.672_501, 0.047_413],
+  [-0.011_820, 0.042_940, 0.968_881],
+];
+const TRITANOPIA: Matrix = [
+  [1.255_528, -0.076_749, -0.178_779],
+  [-0.078_411, 0.930_809, 0.147_602],
+  [0.004_733, 0.691_367, 0.303_900],
+];
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Deficiency {
+  Protanopia,
+  Deuteranopia,
+  Tritanopia,
+}
+
+impl Deficiency {
+  pub const ALL: [Deficiency; 3] = [
+    Deficiency::Protanopia,
+    Deficiency::Deuteranopia,
+    Deficiency::Tritanopia,
+  ];
+
+  fn matrix(self) -> Matrix {
+    match self {
+      Deficiency::Protanopia => PROTANOPIA,
+      Deficiency::Deuteranopia => DEUTERANOPIA,
+      Deficiency::Tritanopia => TRITANOPIA,
+    }
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl RgbColour {
+  // Severity runs from 0 (normal vision) to 1, blending linearly towards the full-severity matrix.
+  pub fn simulate(&self, deficiency: Deficiency, severity: f32) -> RgbColour {
+    let severity = severity.clamp(0.0, 1.0);
+    let linear = self.to_linear();
+    let simulated = apply(&deficiency.matrix(), linear);
+    let blend = |normal: f32, simulated: f32| normal + (simulated - normal) * severity;
+    RgbColour::from_linear(
+      blend(linear.0, simulated.0),
+      blend(linear.1, simulated.1),
+      blend(linear.2, simulated.2),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  mod simulate {
+    use super::*;
+
+    #[test]
+    fn greys_are_unchanged() {
+      let grey = RgbColour::from_tuple(128, 128, 128);
+      for deficiency in Deficiency::ALL {
+        let simulated = grey.simulate(deficiency, 1.0);
+        assert!((simulated.red as i32 - 128).abs() <= 1);
+        assert!((simulated.green as i32 - 128).abs() <= 1);
+        assert!((simulated.blue as i32 - 128).abs() <= 1);
+      }
+    }
+
+    #[test]
+    fn red_and_green_converge() {
+      let red = RgbColour::from_tuple(200, 60, 40);
+      let green = RgbColour::from_tuple(90, 140, 40);
+      let distance = |a: RgbColour, b: RgbColour| (a.red as i32 - b.red as i32).abs();
+      assert!(
+        distance(
+          red.simulate(Deficiency::Deuteranopia, 1.0),
+          green.simulate(Deficiency::Deuteranopia, 1.0)
+        ) < distance(red, green)
+      );
+    }
+
+    #[test]
+    fn zero_severity() {
+      let colour = RgbColour::from_tuple(12, 200, 99);
+      assert_eq!(colour.simulate(Deficiency::Protanopia, 0.0), colour);
+    }
+  }
+}
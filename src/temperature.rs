@@ -0,0 +1,325 @@
+use crate::colour::Chromaticity;
+use crate::colour::RgbColour;
+use crate::colour::XyzColour;
+#[cfg(not(feature = "std"))]
+use crate::math::Float;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+pub const MIN_KELVIN: f32 = 1000.0;
+pub const MAX_KELVIN: f32 = 40000.0;
+
+// Second radiation constant, in metre kelvins.
+const C2: f64 = 1.4388e-2;
+
+// CIE 1931 2° colour matching functions (x̄, ȳ, z̄) from 380nm to 780nm in 10nm steps.
+const COLOUR_MATCHING_FUNCTIONS: [(f64, f64, f64); 41] = [
+  (0.001368, 0.000039, 0.006450),
+  (0.004243, 0.000120, 0.020050),
+  (0.014310, 0.000396, 0.067850),
+  (0.043510, 0.001210, 0.207400),
+  (0.134380, 0.004000, 0.645600),
+  (0.283900, 0.011600, 1.385600),
+  (0.348280, 0.023000, 1.747060),
+  (0.336200, 0.038000, 1.772110),
+  (0.290800, 0.060000, 1.669200),
+  (0.195360, 0.090980, 1.287640),
+  (0.095640, 0.139020, 0.812950),
+  (0.032010, 0.208020, 0.465180),
+  (0.004900, 0.323000, 0.272000),
+  (0.009300, 0.503000, 0.158200),
+  (0.063270, 0.710000, 0.078250),
+  (0.165500, 0.862000, 0.042160),
+  (0.290400, 0.954000, 0.020300),
+  (0.433450, 0.994950, 0.008750),
+  (0.594500, 0.995000, 0.003900),
+  (0.762100, 0.952000, 0.002100),
+  (0.916300, 0.870000, 0.001650),
+  (1.026300, 0.757000, 0.001100),
+  (1.062200, 0.631000, 0.000800),
+  (1.002600, 0.503000, 0.000340),
+  (0.854450, 0.381000, 0.000190),
+  (0.642400, 0.265000, 0.000050),
+  (0.447900, 0.175000, 0.000020),
+  (0.283500, 0.107000, 0.000000),
+  (0.164900, 0.061000, 0.000000),
+  (0.087400, 0.032000, 0.000000),
+  (0.046770, 0.017000, 0.000000),
+  (0.022700, 0.008210, 0.000000),
+  (0.011359, 0.004102, 0.000000),
+  (0.005790, 0.002091, 0.000000),
+  (0.002899, 0.001047, 0.000000),
+  (0.001440, 0.000520, 0.000000),
+  (0.000690, 0.000249, 0.000000),
+  (0.000332, 0.000120, 0.000000),
+  (0.000166, 0.000060, 0.000000),
+  (0.000083, 0.000030, 0.000000),
+  (0.000042, 0.000015, 0.000000),
+];
+
+// Robertson's isotemperature lines: (reciprocal megakelvin, u, v, slope).
+const ISOTEMPERATURE_LINES: [(f32, f32, f32, f32); 31] = [
+  (0.0, 0.18006, 0.26352, -0.24341),
+  (10.0, 0.18066, 0.26589, -0.25479),
+  (20.0, 0.18133, 0.26846, -0.26876),
+  (30.0, 0.18208, 0.27119, -0.28539),
+  (40.0, 0.18293, 0.27407, -0.30470),
+  (50.0, 0.18388, 0.27709, -0.32675),
+  (60.0, 0.18494, 0.28021, -0.35156),
+  (70.0, 0.18611, 0.28342, -0.37915),
+  (80.0, 0.18740, 0.28668, -0.40955),
+  (90.0, 0.18880, 0.28997, -0.44278),
+  (100.0, 0.19032, 0.29326, -0.47888),
+  (125.0, 0.19462, 0.30141, -0.58204),
+  (150.0, 0.19962, 0.30921, -0.70471),
+  (175.0, 0.20525, 0.31647, -0.84901),
+  (200.0, 0.21142, 0.32312, -1.0182),
+  (225.0, 0.21807, 0.32909, -1.2168),
+  (250.0, 0.22511, 0.33439, -1.4512),
+  (275.0, 0.23247, 0.33904, -1.7298),
+  (300.0, 0.24010, 0.34308, -2.0637),
+  (325.0, 0.24792, 0.34655, -2.4681),
+  (350.0, 0.25591, 0.34951, -2.9641),
+  (375.0, 0.26400, 0.35200, -3.5814),
+  (400.0, 0.27218, 0.35407, -4.3633),
+  (425.0, 0.28039, 0.35577, -5.3762),
+  (450.0, 0.28863, 0.35714, -6.7262),
+  (475.0, 0.29685, 0.35823, -8.5955),
+  (500.0, 0.30505, 0.35907, -11.324),
+  (525.0, 0.31320, 0.35968, -15.628),
+  (550.0, 0.32129, 0.36011, -23.325),
+  (575.0, 0.32931, 0.36038, -40.770),
+  (600.0, 0.33724, 0.36051, -116.45),
+];
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TemperatureMethod {
+  McCamy,
+  Robertson,
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct CorrelatedTemperature {
+  pub kelvin: f32,
+  pub duv: f32,
+}
+
+// CIE 1960 UCS coordinates.
+fn to_uv(chromaticity: &Chromaticity) -> (f32, f32) {
+  let denominator = -2.0 * chromaticity.x + 12.0 * chromaticity.y + 3.0;
+  (
+    4.0 * chromaticity.x / denominator,
+    6.0 * chromaticity.y / denominator,
+  )
+}
+
+// Integrates Planck's law against the colour matching functions.
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn planckian_chromaticity(kelvin: f32) -> Chromaticity {
+  let kelvin = kelvin.clamp(MIN_KELVIN, MAX_KELVIN) as f64;
+  let mut xyz = (0.0, 0.0, 0.0);
+
+  for (index, (x, y, z)) in COLOUR_MATCHING_FUNCTIONS.iter().enumerate() {
+    let wavelength = (380.0 + 10.0 * index as f64) * 1e-9;
+    let exitance = wavelength.powi(-5) / ((C2 / (wavelength * kelvin)).exp() - 1.0);
+    xyz.0 += exitance * x;
+    xyz.1 += exitance * y;
+    xyz.2 += exitance * z;
+  }
+
+  let sum = xyz.0 + xyz.1 + xyz.2;
+  Chromaticity::new((xyz.0 / sum) as f32, (xyz.1 / sum) as f32)
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn kelvin_to_rgb(kelvin: f32) -> RgbColour {
+  let xyz = XyzColour::from_chromaticity(&planckian_chromaticity(kelvin), 1.0);
+  let (red, green, blue) = xyz.to_linear_rgb();
+  let (red, green, blue) = (red.max(0.0), green.max(0.0), blue.max(0.0));
+  let brightest = red.max(green).max(blue);
+
+  RgbColour::from_linear(red / brightest, green / brightest, blue / brightest)
+}
+
+fn mccamy(chromaticity: &Chromaticity) -> f32 {
+  let n = (chromaticity.x - 0.3320) / (0.1858 - chromaticity.y);
+  449.0 * n.powi(3) + 3525.0 * n.powi(2) + 6823.3 * n + 5520.33
+}
+
+fn robertson(chromaticity: &Chromaticity) -> Option<f32> {
+  let (u, v) = to_uv(chromaticity);
+  let mut previous: Option<(f32, f32)> = None;
+
+  for &(mired, line_u, line_v, slope) in ISOTEMPERATURE_LINES.iter() {
+    let distance = ((v - line_v) - slope * (u - line_u)) / (1.0 + slope * slope).sqrt();
+
+    if let Some((previous_mired, previous_distance)) = previous {
+      if distance == 0.0 || distance.signum() != previous_distance.signum() {
+        let fraction = previous_distance / (previous_distance - distance);
+        return Some(1_000_000.0 / (previous_mired + fraction * (mired - previous_mired)));
+      }
+    }
+
+    previous = Some((mired, distance));
+  }
+
+  None
+}
+
+// Signed distance from the Planckian locus in CIE 1960 UCS, positive above the locus.
+fn duv(chromaticity: &Chromaticity, kelvin: f32) -> f32 {
+  let (u, v) = to_uv(chromaticity);
+  let (locus_u, locus_v) = to_uv(&planckian_chromaticity(kelvin));
+  let distance = ((u - locus_u).powi(2) + (v - locus_v).powi(2)).sqrt();
+
+  if v < locus_v {
+    -distance
+  } else {
+    distance
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+pub fn correlated_temperature(
+  chromaticity: &Chromaticity,
+  method: TemperatureMethod,
+) -> Option<CorrelatedTemperature> {
+  let kelvin = match method {
+    TemperatureMethod::McCamy => Some(mccamy(chromaticity)),
+    TemperatureMethod::Robertson => robertson(chromaticity),
+  }?;
+
+  if !kelvin.is_finite() || kelvin <= 0.0 {
+    return None;
+  }
+
+  Some(CorrelatedTemperature {
+    kelvin,
+    duv: duv(chromaticity, kelvin),
+  })
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl RgbColour {
+  pub fn correlated_temperature(&self, method: TemperatureMethod) -> Option<CorrelatedTemperature> {
+    correlated_temperature(&self.to_xyz().chromaticity(), method)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::colour::D65;
+
+  fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+    assert!(
+      (actual - expected).abs() < tolerance,
+      "{} != {}",
+      actual,
+      expected
+    );
+  }
+
+  mod planckian_chromaticity {
+    use super::*;
+
+    #[test]
+    fn illuminant_a() {
+      let chromaticity = planckian_chromaticity(2856.0);
+      assert_close(chromaticity.x, 0.44757, 0.0001);
+      assert_close(chromaticity.y, 0.40745, 0.0002);
+    }
+
+    #[test]
+    fn clamped_to_range() {
+      assert_eq!(
+        planckian_chromaticity(500.0),
+        planckian_chromaticity(1000.0)
+      );
+      assert_eq!(
+        planckian_chromaticity(90000.0),
+        planckian_chromaticity(40000.0)
+      );
+    }
+  }
+
+  mod kelvin_to_rgb {
+    use super::*;
+
+    #[test]
+    fn candle_light_is_orange() {
+      let colour = kelvin_to_rgb(1900.0);
+      assert_eq!(colour.red, 255);
+      assert!(colour.green > 100 && colour.green < 160);
+      assert!(colour.blue < 60);
+    }
+
+    #[test]
+    fn daylight_is_nearly_white() {
+      let colour = kelvin_to_rgb(6500.0);
+      assert!(colour.red >= 250 && colour.green >= 240 && colour.blue >= 240);
+    }
+
+    #[test]
+    fn blue_sky_is_blue() {
+      let colour = kelvin_to_rgb(40000.0);
+      assert_eq!(colour.blue, 255);
+      assert!(colour.red < colour.green && colour.green < colour.blue);
+    }
+  }
+
+  mod correlated_temperature {
+    use super::*;
+
+    #[test]
+    fn d65_mccamy() {
+      let result = correlated_temperature(&D65, TemperatureMethod::McCamy).unwrap();
+      assert_close(result.kelvin, 6504.0, 2.0);
+      assert_close(result.duv, 0.0032, 0.0001);
+    }
+
+    #[test]
+    fn d65_robertson() {
+      let result = correlated_temperature(&D65, TemperatureMethod::Robertson).unwrap();
+      assert_close(result.kelvin, 6503.0, 2.0);
+      assert_close(result.duv, 0.0032, 0.0001);
+    }
+
+    #[test]
+    fn illuminant_a() {
+      let chromaticity = Chromaticity::new(0.44757, 0.40745);
+      let result = correlated_temperature(&chromaticity, TemperatureMethod::Robertson).unwrap();
+      assert_close(result.kelvin, 2856.0, 2.0);
+      assert_close(result.duv, 0.0, 0.0001);
+    }
+
+    #[test]
+    fn round_trip() {
+      for &kelvin in &[2000.0, 3500.0, 5000.0, 9000.0] {
+        let chromaticity = planckian_chromaticity(kelvin);
+        let result = correlated_temperature(&chromaticity, TemperatureMethod::Robertson).unwrap();
+        assert_close(result.kelvin / kelvin, 1.0, 0.002);
+        assert_close(result.duv, 0.0, 0.0001);
+      }
+    }
+
+    #[test]
+    fn below_robertson_range() {
+      let chromaticity = planckian_chromaticity(1200.0);
+      assert_eq!(
+        correlated_temperature(&chromaticity, TemperatureMethod::Robertson),
+        None
+      );
+    }
+
+    #[test]
+    fn white_rgb() {
+      let result = RgbColour::from_tuple(255, 255, 255)
+        .correlated_temperature(TemperatureMethod::Robertson)
+        .unwrap();
+      assert_close(result.kelvin, 6504.0, 10.0);
+    }
+  }
+}
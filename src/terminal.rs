@@ -0,0 +1,262 @@
+use crate::colour::RgbColour;
+use crate::comparison::Metric;
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+// xterm's default values for the basic 16 colours; terminals are free to theme these.
+const BASIC_COLOURS: [(u8, u8, u8); 16] = [
+  (0, 0, 0),
+  (205, 0, 0),
+  (0, 205, 0),
+  (205, 205, 0),
+  (0, 0, 238),
+  (205, 0, 205),
+  (0, 205, 205),
+  (229, 229, 229),
+  (127, 127, 127),
+  (255, 0, 0),
+  (0, 255, 0),
+  (255, 255, 0),
+  (92, 92, 255),
+  (255, 0, 255),
+  (0, 255, 255),
+  (255, 255, 255),
+];
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum ColourDepth {
+  None,
+  Ansi16,
+  Ansi256,
+  TrueColour,
+}
+
+impl ColourDepth {
+  pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> ColourDepth {
+    let colorterm = colorterm.unwrap_or_default().to_ascii_lowercase();
+    let term = term.unwrap_or_default().to_ascii_lowercase();
+
+    if colorterm == "truecolor"
+      || colorterm == "24bit"
+      || term.contains("truecolor")
+      || term.contains("direct")
+    {
+      ColourDepth::TrueColour
+    } else if term.is_empty() || term == "dumb" {
+      ColourDepth::None
+    } else if term.contains("256color") {
+      ColourDepth::Ansi256
+    } else {
+      ColourDepth::Ansi16
+    }
+  }
+
+  // Reads COLORTERM and TERM, and turns colour off entirely when NO_COLOR is set.
+  #[cfg(feature = "std")]
+  pub fn detect() -> ColourDepth {
+    if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+      return ColourDepth::None;
+    }
+
+    ColourDepth::from_env(
+      std::env::var("COLORTERM").ok().as_deref(),
+      std::env::var("TERM").ok().as_deref(),
+    )
+  }
+}
+
+fn xterm_colour(index: u8) -> RgbColour {
+  match index {
+    0..=15 => {
+      let (red, green, blue) = BASIC_COLOURS[index as usize];
+      RgbColour::from_tuple(red, green, blue)
+    }
+    16..=231 => {
+      let index = index as usize - 16;
+      RgbColour::from_tuple(
+        CUBE_LEVELS[index / 36],
+        CUBE_LEVELS[index / 6 % 6],
+        CUBE_LEVELS[index % 6],
+      )
+    }
+    _ => {
+      let grey = 8 + (index - 232) * 10;
+      RgbColour::from_tuple(grey, grey, grey)
+    }
+  }
+}
+
+fn nearest(colour: &RgbColour, indices: core::ops::RangeInclusive<u8>) -> u8 {
+  let target = Metric::Lab.coordinates(*colour);
+  indices
+    .map(|index| {
+      let candidate = Metric::Lab.coordinates(xterm_colour(index));
+      (index, Metric::Lab.distance(&target, &candidate))
+    })
+    .fold((0, f32::INFINITY), |nearest, candidate| {
+      if candidate.1 < nearest.1 {
+        candidate
+      } else {
+        nearest
+      }
+    })
+    .0
+}
+
+fn escape(colour: &RgbColour, depth: ColourDepth, background: bool) -> String {
+  match depth {
+    ColourDepth::None => String::new(),
+    ColourDepth::Ansi16 => {
+      let index = colour.to_ansi16();
+      let base = match (background, index < 8) {
+        (false, true) => 30,
+        (false, false) => 82,
+        (true, true) => 40,
+        (true, false) => 92,
+      };
+      format!("\x1b[{}m", base + index)
+    }
+    ColourDepth::Ansi256 => format!(
+      "\x1b[{};5;{}m",
+      if background { 48 } else { 38 },
+      colour.to_ansi256()
+    ),
+    ColourDepth::TrueColour => format!(
+      "\x1b[{};2;{};{};{}m",
+      if background { 48 } else { 38 },
+      colour.red,
+      colour.green,
+      colour.blue
+    ),
+  }
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl RgbColour {
+  // Nearest entry in the xterm colour cube and grey ramp (16-255), by Lab distance.
+  pub fn to_ansi256(&self) -> u8 {
+    nearest(self, 16..=255)
+  }
+
+  pub fn to_ansi16(&self) -> u8 {
+    nearest(self, 0..=15)
+  }
+
+  pub fn to_ansi_foreground(&self, depth: ColourDepth) -> String {
+    escape(self, depth, false)
+  }
+
+  pub fn to_ansi_background(&self, depth: ColourDepth) -> String {
+    escape(self, depth, true)
+  }
+
+  // Wraps text in the foreground colour and resets afterwards.
+  pub fn paint(&self, text: &str, depth: ColourDepth) -> String {
+    match depth {
+      ColourDepth::None => text.to_string(),
+      depth => format!("{}{}\x1b[0m", self.to_ansi_foreground(depth), text),
+    }
+  }
+
+  pub fn swatch(&self, width: usize, depth: ColourDepth) -> String {
+    match depth {
+      ColourDepth::None => String::new(),
+      depth => format!(
+        "{}{}\x1b[0m",
+        self.to_ansi_background(depth),
+        " ".repeat(width)
+      ),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  mod from_env {
+    use super::*;
+
+    #[test]
+    fn capabilities() {
+      assert_eq!(
+        ColourDepth::from_env(Some("truecolor"), Some("xterm")),
+        ColourDepth::TrueColour
+      );
+      assert_eq!(
+        ColourDepth::from_env(None, Some("xterm-direct")),
+        ColourDepth::TrueColour
+      );
+      assert_eq!(
+        ColourDepth::from_env(None, Some("iterm2-truecolor")),
+        ColourDepth::TrueColour
+      );
+      assert_eq!(
+        ColourDepth::from_env(None, Some("xterm-256color")),
+        ColourDepth::Ansi256
+      );
+      assert_eq!(
+        ColourDepth::from_env(None, Some("xterm")),
+        ColourDepth::Ansi16
+      );
+      assert_eq!(ColourDepth::from_env(None, Some("dumb")), ColourDepth::None);
+      assert_eq!(ColourDepth::from_env(None, None), ColourDepth::None);
+    }
+  }
+
+  mod to_ansi256 {
+    use super::*;
+
+    #[test]
+    fn exact() {
+      assert_eq!(RgbColour::from_tuple(255, 0, 0).to_ansi256(), 196);
+      assert_eq!(RgbColour::from_tuple(95, 135, 175).to_ansi256(), 67);
+      assert_eq!(RgbColour::from_tuple(128, 128, 128).to_ansi256(), 244);
+    }
+
+    #[test]
+    fn nearest() {
+      assert_eq!(RgbColour::from_tuple(250, 5, 10).to_ansi256(), 196);
+      assert_eq!(RgbColour::from_tuple(3, 3, 3).to_ansi256(), 16);
+    }
+  }
+
+  mod to_ansi16 {
+    use super::*;
+
+    #[test]
+    fn nearest() {
+      assert_eq!(RgbColour::from_tuple(250, 5, 10).to_ansi16(), 9);
+      assert_eq!(RgbColour::from_tuple(10, 10, 10).to_ansi16(), 0);
+      assert_eq!(RgbColour::from_tuple(0, 0, 180).to_ansi16(), 4);
+    }
+  }
+
+  mod escapes {
+    use super::*;
+
+    #[test]
+    fn depths() {
+      let colour = RgbColour::from_tuple(255, 0, 0);
+      assert_eq!(
+        colour.to_ansi_foreground(ColourDepth::TrueColour),
+        "\x1b[38;2;255;0;0m"
+      );
+      assert_eq!(
+        colour.to_ansi_background(ColourDepth::Ansi256),
+        "\x1b[48;5;196m"
+      );
+      assert_eq!(colour.to_ansi_foreground(ColourDepth::Ansi16), "\x1b[91m");
+      assert_eq!(colour.to_ansi_background(ColourDepth::Ansi16), "\x1b[101m");
+      assert_eq!(colour.paint("text", ColourDepth::None), "text");
+      assert_eq!(
+        colour.swatch(2, ColourDepth::Ansi256),
+        "\x1b[48;5;196m  \x1b[0m"
+      );
+    }
+  }
+}
@@ -0,0 +1,405 @@
+use crate::colour::RgbColour;
+use crate::palette::Palette;
+use crate::prelude::*;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TokenFormat {
+  Css,
+  Scss,
+  Tailwind,
+  Dtcg,
+  AndroidXml,
+  IosColorset,
+}
+
+struct Group {
+  name: Option<String>,
+  tokens: Vec<(String, RgbColour)>,
+}
+
+// Lowercases and joins runs of anything that isn't a letter or digit with the separator.
+pub(crate) fn slug(value: &str, separator: char) -> String {
+  let mut slug = String::new();
+  for character in value.chars() {
+    if character.is_alphanumeric() {
+      slug.extend(character.to_lowercase());
+    } else if !slug.is_empty() && !slug.ends_with(separator) {
+      slug.push(separator);
+    }
+  }
+  slug.trim_end_matches(separator).to_string()
+}
+
+// Appends `-2`, `-3` and so on to a token that is already taken.
+fn unique(token: String, separator: char, taken: impl Fn(&str) -> bool) -> String {
+  let mut candidate = token.clone();
+  let mut suffix = 1;
+  while taken(&candidate) {
+    suffix += 1;
+    candidate = format!("{}{}{}", token, separator, suffix);
+  }
+  candidate
+}
+
+fn deduplicate(
+  tokens: impl IntoIterator<Item = (String, RgbColour)>,
+  separator: char,
+) -> Vec<(String, RgbColour)> {
+  let mut unique_tokens: Vec<(String, RgbColour)> = vec![];
+  for (token, colour) in tokens {
+    let token = unique(token, separator, |candidate| {
+      unique_tokens.iter().any(|(token, _)| token == candidate)
+    });
+    unique_tokens.push((token, colour));
+  }
+  unique_tokens
+}
+
+// Swatches sharing a group are collected under its first appearance; unnamed swatches are numbered.
+fn groups(palette: &Palette) -> Vec<Group> {
+  let mut groups: Vec<Group> = vec![];
+  for swatch in palette.swatches() {
+    let name = swatch.group().map(|group| slug(&group, '-'));
+    let index = match groups.iter().position(|group| group.name == name) {
+      Some(index) => index,
+      None => {
+        groups.push(Group {
+          name,
+          tokens: vec![],
+        });
+        groups.len() - 1
+      }
+    };
+
+    let tokens = &mut groups[index].tokens;
+    let token = match slug(&swatch.name(), '-') {
+      slug if slug.is_empty() => format!("colour-{}", tokens.len() + 1),
+      slug => slug,
+    };
+    let token = unique(token, '-', |candidate| {
+      tokens.iter().any(|(token, _)| token == candidate)
+    });
+    tokens.push((token, swatch.to_rgb()));
+  }
+  groups
+}
+
+// Group prefixes can make a token match an ungrouped one, so these are made unique again.
+fn flat(palette: &Palette, separator: char) -> Vec<(String, RgbColour)> {
+  let tokens = groups(palette)
+    .into_iter()
+    .flat_map(|Group { name, tokens }| {
+      tokens.into_iter().map(move |(token, colour)| {
+        let token = match &name {
+          Some(name) => format!("{}-{}", name, token),
+          None => token,
+        };
+        (token.replace('-', &separator.to_string()), colour)
+      })
+    })
+    .collect::<Vec<_>>();
+  deduplicate(tokens, separator)
+}
+
+fn quote(value: &str) -> String {
+  let mut quoted = String::from("\"");
+  for character in value.chars() {
+    match character {
+      '"' => quoted += "\\\"",
+      '\\' => quoted += "\\\\",
+      '\n' => quoted += "\\n",
+      character if (character as u32) < 0x20 => quoted += &format!("\\u{:04x}", character as u32),
+      character => quoted.push(character),
+    }
+  }
+  quoted.push('"');
+  quoted
+}
+
+fn hex(colour: &RgbColour) -> String {
+  format!("#{}", colour.to_hex())
+}
+
+// Nested `"key": value` objects shared by the SCSS, Tailwind and DTCG outputs.
+fn nested(
+  palette: &Palette,
+  indent: usize,
+  separator: &str,
+  trailing: bool,
+  open: &str,
+  close: &str,
+  value: impl Fn(&RgbColour, usize) -> String,
+) -> String {
+  let pad = |depth: usize| "  ".repeat(depth);
+  let mut entries = vec![];
+  for group in groups(palette) {
+    let tokens = |depth: usize| {
+      group
+        .tokens
+        .iter()
+        .map(|(token, colour)| format!("{}{}: {}", pad(depth), quote(token), value(colour, depth)))
+        .collect::<Vec<_>>()
+    };
+    match &group.name {
+      Some(name) => {
+        let mut inner = tokens(indent + 1).join(separator);
+        if trailing {
+          inner += separator.trim_end();
+        }
+        entries.push(format!(
+          "{}{}: {}\n{}\n{}{}",
+          pad(indent),
+          quote(name),
+          open,
+          inner,
+          pad(indent),
+          close
+        ));
+      }
+      None => entries.extend(tokens(indent)),
+    }
+  }
+
+  let mut output = entries.join(separator);
+  if trailing && !output.is_empty() {
+    output += separator.trim_end();
+  }
+  output
+}
+
+fn css(palette: &Palette) -> String {
+  let mut output = String::from(":root {\n");
+  for (token, colour) in flat(palette, '-') {
+    output += &format!("  --{}: {};\n", token, hex(&colour));
+  }
+  output + "}\n"
+}
+
+fn scss(palette: &Palette) -> String {
+  let name = palette
+    .name()
+    .map(|name| slug(&name, '-'))
+    .filter(|name| !name.is_empty())
+    .unwrap_or_else(|| "colours".to_string());
+  format!(
+    "${}: (\n{}\n);\n",
+    name,
+    nested(palette, 1, ",\n", true, "(", ")", |colour, _| hex(colour))
+  )
+}
+
+fn tailwind(palette: &Palette) -> String {
+  let colours = nested(palette, 3, ",\n", true, "{", "}", |colour, _| {
+    quote(&hex(colour))
+  });
+  format!(
+    "module.exports = {{\n  theme: {{\n    colors: {{\n{}\n    }},\n  }},\n}};\n",
+    colours
+  )
+}
+
+fn dtcg(palette: &Palette) -> String {
+  format!(
+    "{{\n{}\n}}\n",
+    nested(palette, 1, ",\n", false, "{", "}", |colour, depth| {
+      let pad = "  ".repeat(depth);
+      format!(
+        "{{\n{}  \"$type\": \"color\",\n{}  \"$value\": {}\n{}}}",
+        pad,
+        pad,
+        quote(&hex(colour)),
+        pad
+      )
+    })
+  )
+}
+
+// Resource names have to start with a letter.
+fn android_xml(palette: &Palette) -> String {
+  let tokens = flat(palette, '_').into_iter().map(|(token, colour)| {
+    if token.starts_with(|character: char| character.is_ascii_digit()) {
+      (format!("colour_{}", token), colour)
+    } else {
+      (token, colour)
+    }
+  });
+  let mut output = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>\n");
+  for (token, colour) in deduplicate(tokens, '_') {
+    output += &format!(
+      "    <color name=\"{}\">#FF{}</color>\n",
+      token,
+      colour.to_hex().to_uppercase()
+    );
+  }
+  output + "</resources>\n"
+}
+
+// One `Contents.json` per colour set, keyed by the `.colorset` directory it belongs in.
+fn ios_colorset(palette: &Palette) -> String {
+  let colour_sets = flat(palette, '-')
+    .iter()
+    .map(|(token, colour)| {
+      format!(
+        concat!(
+          "  {}: {{\n",
+          "    \"colors\": [\n",
+          "      {{\n",
+          "        \"color\": {{\n",
+          "          \"color-space\": \"srgb\",\n",
+          "          \"components\": {{\n",
+          "            \"alpha\": \"1.000\",\n",
+          "            \"blue\": \"0x{:02X}\",\n",
+          "            \"green\": \"0x{:02X}\",\n",
+          "            \"red\": \"0x{:02X}\"\n",
+          "          }}\n",
+          "        }},\n",
+          "        \"idiom\": \"universal\"\n",
+          "      }}\n",
+          "    ],\n",
+          "    \"info\": {{\n",
+          "      \"author\": \"xcode\",\n",
+          "      \"version\": 1\n",
+          "    }}\n",
+          "  }}"
+        ),
+        quote(&format!("{}.colorset", token)),
+        colour.blue,
+        colour.green,
+        colour.red
+      )
+    })
+    .collect::<Vec<_>>();
+  format!("{{\n{}\n}}\n", colour_sets.join(",\n"))
+}
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+impl Palette {
+  // Swatch names become token names, and groups become nested objects or name prefixes. Names that
+  // end up the same get a numeric suffix.
+  pub fn to_tokens(&self, format: TokenFormat) -> String {
+    match format {
+      TokenFormat::Css => css(self),
+      TokenFormat::Scss => scss(self),
+      TokenFormat::Tailwind => tailwind(self),
+      TokenFormat::Dtcg => dtcg(self),
+      TokenFormat::AndroidXml => android_xml(self),
+      TokenFormat::IosColorset => ios_colorset(self),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::palette::Swatch;
+
+  fn palette() -> Palette {
+    let mut palette = Palette::new();
+    palette.set_name("Brand Colours");
+    palette.push(&Swatch::new(
+      "Primary Red",
+      &RgbColour::from_tuple(255, 0, 0),
+    ));
+    palette.push(&Swatch::new("100", &RgbColour::from_tuple(0, 128, 128)).with_group("Teal"));
+    palette.push(&Swatch::new("", &RgbColour::from_tuple(0, 64, 64)).with_group("Teal"));
+    palette
+  }
+
+  mod to_tokens {
+    use super::*;
+
+    #[test]
+    fn css() {
+      assert_eq!(
+        palette().to_tokens(TokenFormat::Css),
+        ":root {\n  --primary-red: #ff0000;\n  --teal-100: #008080;\n  --teal-colour-2: #004040;\n}\n"
+      );
+    }
+
+    #[test]
+    fn scss() {
+      assert_eq!(
+        palette().to_tokens(TokenFormat::Scss),
+        "$brand-colours: (\n  \"primary-red\": #ff0000,\n  \"teal\": (\n    \"100\": #008080,\n    \"colour-2\": #004040,\n  ),\n);\n"
+      );
+    }
+
+    #[test]
+    fn tailwind() {
+      let tokens = palette().to_tokens(TokenFormat::Tailwind);
+      assert!(tokens.starts_with("module.exports = {\n  theme: {\n    colors: {\n"));
+      assert!(tokens.contains("      \"teal\": {\n        \"100\": \"#008080\",\n"));
+    }
+
+    #[test]
+    fn json_formats() {
+      let dtcg: serde_json::Value =
+        serde_json::from_str(&palette().to_tokens(TokenFormat::Dtcg)).unwrap();
+      assert_eq!(dtcg["teal"]["100"]["$value"], "#008080");
+      assert_eq!(dtcg["primary-red"]["$type"], "color");
+
+      let ios: serde_json::Value =
+        serde_json::from_str(&palette().to_tokens(TokenFormat::IosColorset)).unwrap();
+      let components = &ios["teal-100.colorset"]["colors"][0]["color"]["components"];
+      assert_eq!(components["green"], "0x80");
+      assert_eq!(components["red"], "0x00");
+    }
+
+    #[test]
+    fn android_xml() {
+      assert!(palette()
+        .to_tokens(TokenFormat::AndroidXml)
+        .contains("    <color name=\"teal_100\">#FF008080</color>\n"));
+    }
+
+    #[test]
+    fn leading_digits() {
+      let mut palette = Palette::new();
+      palette.push(&Swatch::new(
+        "Colour 100",
+        &RgbColour::from_tuple(0, 64, 64),
+      ));
+      palette.push(&Swatch::new("100", &RgbColour::from_tuple(0, 128, 128)));
+      let xml = palette.to_tokens(TokenFormat::AndroidXml);
+      assert!(xml.contains("    <color name=\"colour_100\">#FF004040</color>\n"));
+      assert!(xml.contains("    <color name=\"colour_100_2\">#FF008080</color>\n"));
+    }
+
+    #[test]
+    fn duplicate_slugs() {
+      let mut palette = Palette::new();
+      palette.push(&Swatch::new(
+        "Primary Red",
+        &RgbColour::from_tuple(255, 0, 0),
+      ));
+      palette.push(&Swatch::new(
+        "primary-red",
+        &RgbColour::from_tuple(200, 0, 0),
+      ));
+      palette.push(&Swatch::new(
+        "Teal 100",
+        &RgbColour::from_tuple(0, 128, 128),
+      ));
+      palette.push(&Swatch::new("100", &RgbColour::from_tuple(0, 64, 64)).with_group("Teal"));
+      assert_eq!(
+        palette.to_tokens(TokenFormat::Css),
+        ":root {\n  --primary-red: #ff0000;\n  --primary-red-2: #c80000;\n  --teal-100: #008080;\n  --teal-100-2: #004040;\n}\n"
+      );
+
+      let dtcg: serde_json::Value =
+        serde_json::from_str(&palette.to_tokens(TokenFormat::Dtcg)).unwrap();
+      assert_eq!(dtcg["primary-red"]["$value"], "#ff0000");
+      assert_eq!(dtcg["primary-red-2"]["$value"], "#c80000");
+    }
+
+    #[test]
+    fn empty() {
+      assert_eq!(Palette::new().to_tokens(TokenFormat::Css), ":root {\n}\n");
+      let dtcg = Palette::new().to_tokens(TokenFormat::Dtcg);
+      assert!(serde_json::from_str::<serde_json::Value>(&dtcg).is_ok());
+    }
+  }
+}
@@ -6,7 +6,6 @@
 extern crate wasm_bindgen_test;
 use colour_fun::colour::*;
 use colour_fun::error_code::*;
-use wasm_bindgen::prelude::*;
 use wasm_bindgen_test::*;
 
 wasm_bindgen_test_configure!(run_in_browser);
@@ -47,9 +46,7 @@ fn valid_pink_is_hex_ffc0cb() {
 fn invalid_rust_is_hex_invalid() {
     assert_eq!(
         RgbColour::from_colour_name("rust"),
-        Err(JsValue::from_str(&String::from(
-            ErrorCode::InvalidColourName("rust".to_string())
-        )))
+        Err(ErrorCode::InvalidColourName("rust".to_string()))
     )
 }
 
@@ -87,3 +84,25 @@ fn invalid_5fs_is_not_valid_colour() {
 fn invalid_rust_is_not_valid_colour() {
     assert!(!is_valid_colour("rust"))
 }
+
+#[wasm_bindgen_test]
+fn hashed_colour_matches_native_build() {
+    assert_eq!(
+        colour_fun::hash::colour_from_id("user-1", &colour_fun::hash::HashColourOptions::new()),
+        RgbColour::from_tuple(124, 135, 216)
+    )
+}
+
+#[wasm_bindgen_test]
+fn filter_writes_back_into_image_data() {
+    let pixels = [255, 0, 0, 255, 0, 0, 255, 128];
+    let image_data = web_sys::ImageData::new_with_u8_clamped_array(
+        wasm_bindgen::Clamped(&pixels[..]),
+        2,
+    )
+    .unwrap();
+    colour_fun::filters::Filter::brightness_contrast(0.5, 1.0)
+        .apply_to_image_data(&image_data)
+        .unwrap();
+    assert_eq!(image_data.data().0, vec![128, 0, 0, 255, 0, 0, 128, 128])
+}